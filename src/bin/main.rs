@@ -1,7 +1,7 @@
 use s3_client::error::Result;
 
 #[tokio::main]
-fn main() -> Result<()> {
+async fn main() -> Result<()> {
     println!("Hello, world!");
 
     Ok(())
@@ -0,0 +1,130 @@
+//! A synchronous client for code that isn't async, e.g. CLI tools and scripts
+
+use crate::client::S3Client;
+use crate::error::{Result, S3ClientError};
+use crate::tokio::HyperConnector;
+use crate::types::{
+    ByteRange, CopyObjectOutput, CopyObjectRequest, DeleteObjectsOutput, GetObjectOutput,
+    ListBucketsOutput, ListObjectsV2Output, ObjectMetadata, PutObjectOutput, PutObjectRequest,
+};
+use bytes::Bytes;
+use std::future::Future;
+use tokio::runtime::Handle;
+
+/// Wraps an [`S3Client`], blocking the calling thread until each request completes
+///
+/// The requests run on a tokio runtime, so the methods must not be called from
+/// within one and return [`S3ClientError::BlockingInAsyncContext`] if they are
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use s3_client::client::S3ClientBuilder;
+/// # use s3_client::credentials::EnvironmentCredentialProvider;
+/// let client = S3ClientBuilder::default()
+///     .endpoint("https://s3.us-east-1.amazonaws.com")
+///     .credentials(Arc::new(EnvironmentCredentialProvider::new()))
+///     .build_blocking()?;
+/// let object = client.get("bucket", "key", None)?;
+/// # Ok::<(), s3_client::error::S3ClientError>(())
+/// ```
+pub struct BlockingS3Client {
+    client: S3Client<HyperConnector>,
+    runtime: Handle,
+}
+
+impl BlockingS3Client {
+    /// Wraps `client`, running its requests on `runtime`
+    pub fn new(client: S3Client<HyperConnector>, runtime: Handle) -> Self {
+        Self { client, runtime }
+    }
+
+    /// Returns the wrapped async client
+    pub fn client(&self) -> &S3Client<HyperConnector> {
+        &self.client
+    }
+
+    /// Runs `future` to completion on the runtime, unless called from within one
+    fn block_on<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        if Handle::try_current().is_ok() {
+            return Err(S3ClientError::BlockingInAsyncContext);
+        }
+        self.runtime.block_on(future)
+    }
+
+    /// See [`S3Client::get_object`], returning only the body
+    pub fn get(&self, bucket: &str, key: &str, range: Option<ByteRange>) -> Result<Bytes> {
+        Ok(self.get_object(bucket, key, range)?.body)
+    }
+
+    /// See [`S3Client::get_object`]
+    pub fn get_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        range: Option<ByteRange>,
+    ) -> Result<GetObjectOutput> {
+        self.block_on(self.client.get_object(bucket, key, range))
+    }
+
+    /// See [`S3Client::put_object`]
+    pub fn put_object(&self, bucket: &str, key: &str, body: Bytes) -> Result<PutObjectOutput> {
+        self.block_on(self.client.put_object(bucket, key, body))
+    }
+
+    /// See [`S3Client::put_object_with`]
+    pub fn put_object_with(&self, request: PutObjectRequest) -> Result<PutObjectOutput> {
+        self.block_on(self.client.put_object_with(request))
+    }
+
+    /// See [`S3Client::copy_object`]
+    pub fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+    ) -> Result<CopyObjectOutput> {
+        self.block_on(
+            self.client
+                .copy_object(src_bucket, src_key, dst_bucket, dst_key),
+        )
+    }
+
+    /// See [`S3Client::copy_object_with`]
+    pub fn copy_object_with(&self, request: CopyObjectRequest) -> Result<CopyObjectOutput> {
+        self.block_on(self.client.copy_object_with(request))
+    }
+
+    /// See [`S3Client::head_object`]
+    pub fn head_object(&self, bucket: &str, key: &str) -> Result<ObjectMetadata> {
+        self.block_on(self.client.head_object(bucket, key))
+    }
+
+    /// See [`S3Client::delete_object`]
+    pub fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        self.block_on(self.client.delete_object(bucket, key))
+    }
+
+    /// See [`S3Client::delete_objects`]
+    pub fn delete_objects(&self, bucket: &str, keys: &[String]) -> Result<DeleteObjectsOutput> {
+        self.block_on(self.client.delete_objects(bucket, keys))
+    }
+
+    /// See [`S3Client::list_buckets`]
+    pub fn list_buckets(&self) -> Result<ListBucketsOutput> {
+        self.block_on(self.client.list_buckets())
+    }
+
+    /// See [`S3Client::list_objects_v2`]
+    pub fn list_objects_v2(
+        &self,
+        bucket: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<&str>,
+    ) -> Result<ListObjectsV2Output> {
+        self.block_on(
+            self.client
+                .list_objects_v2(bucket, prefix, continuation_token),
+        )
+    }
+}
@@ -0,0 +1,178 @@
+//! Additional checksums S3 uses to verify the integrity of objects
+//!
+//! <https://docs.aws.amazon.com/AmazonS3/latest/userguide/checking-object-integrity.html>
+
+use crate::error::{Result, S3ClientError};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use hyper::HeaderMap;
+use md5::{Digest, Md5};
+use ring::digest;
+
+/// The header requesting that S3 returns the checksums of an object
+pub(crate) const CHECKSUM_MODE_HEADER: &str = "x-amz-checksum-mode";
+
+/// The base64 encoded MD5 digest of a request body, required by e.g. `DeleteObjects`
+pub(crate) const CONTENT_MD5_HEADER: &str = "content-md5";
+
+/// Returns the base64 encoded MD5 digest of `body`, as expected by `Content-MD5`
+pub(crate) fn content_md5(body: &[u8]) -> String {
+    BASE64_STANDARD.encode(Md5::digest(body))
+}
+
+/// An algorithm S3 supports for checksums of objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Crc32,
+    Crc32c,
+    Sha1,
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    const ALL: [Self; 4] = [Self::Crc32, Self::Crc32c, Self::Sha1, Self::Sha256];
+
+    /// Returns the header carrying a checksum of this algorithm, e.g. `x-amz-checksum-crc32c`
+    pub(crate) fn header(&self) -> &'static str {
+        match self {
+            Self::Crc32 => "x-amz-checksum-crc32",
+            Self::Crc32c => "x-amz-checksum-crc32c",
+            Self::Sha1 => "x-amz-checksum-sha1",
+            Self::Sha256 => "x-amz-checksum-sha256",
+        }
+    }
+
+    /// Returns the base64 encoded checksum of `bytes`, as sent in [`Self::header`]
+    pub(crate) fn checksum(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Crc32 => BASE64_STANDARD.encode(crc32(bytes).to_be_bytes()),
+            Self::Crc32c => BASE64_STANDARD.encode(crc32_with(&CRC32C_TABLE, bytes).to_be_bytes()),
+            Self::Sha1 => {
+                BASE64_STANDARD.encode(digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, bytes))
+            }
+            Self::Sha256 => BASE64_STANDARD.encode(digest::digest(&digest::SHA256, bytes)),
+        }
+    }
+}
+
+/// Verifies `body` against the checksum in `headers`, if S3 returned one
+///
+/// Checksums of objects uploaded in parts, of the form `checksum-parts`, cover
+/// the checksums of the parts rather than the body and are not verified
+pub(crate) fn verify(headers: &HeaderMap, body: &[u8]) -> Result<()> {
+    for algorithm in ChecksumAlgorithm::ALL {
+        let expected = match headers.get(algorithm.header()).map(|v| v.to_str()) {
+            Some(Ok(expected)) if !expected.contains('-') => expected,
+            _ => continue,
+        };
+
+        let actual = algorithm.checksum(body);
+        if actual != expected {
+            return Err(S3ClientError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Lookup table of CRC-32 (IEEE), as used by e.g. gzip
+static CRC32_TABLE: [u32; 256] = crc32_table(0xEDB8_8320);
+
+/// Lookup table of CRC-32C (Castagnoli), as used by e.g. iSCSI
+static CRC32C_TABLE: [u32; 256] = crc32_table(0x82F6_3B78);
+
+/// Builds the lookup table of the reflected CRC-32 `polynomial`
+const fn crc32_table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ polynomial,
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Returns the CRC-32 (IEEE) of `bytes`
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    crc32_with(&CRC32_TABLE, bytes)
+}
+
+fn crc32_with(table: &[u32; 256], bytes: &[u8]) -> u32 {
+    let crc = bytes.iter().fold(!0, |crc, byte| {
+        table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    });
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_md5() {
+        // https://www.rfc-editor.org/rfc/rfc1321#appendix-A.5
+        assert_eq!(content_md5(b""), "1B2M2Y8AsgTpgAmY7PhCfg==");
+        assert_eq!(content_md5(b"abc"), "kAFQmDzST7DWlj99KOF/cg==");
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32_with(&CRC32C_TABLE, b"123456789"), 0xE306_9283);
+        assert_eq!(crc32_with(&CRC32C_TABLE, b""), 0);
+
+        let cases = [
+            (ChecksumAlgorithm::Crc32, "y/Q5Jg=="),
+            (ChecksumAlgorithm::Crc32c, "4waSgw=="),
+            (ChecksumAlgorithm::Sha1, "98O8HYCOBHMq32eZZczDTKeuNEE="),
+            (
+                ChecksumAlgorithm::Sha256,
+                "FeKw08M4keuw8e9gnsQZQgwg4yDOlMZfvIwzEkSOsiU=",
+            ),
+        ];
+        for (algorithm, expected) in cases {
+            assert_eq!(algorithm.checksum(b"123456789"), expected, "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut headers = HeaderMap::new();
+        assert!(verify(&headers, b"123456789").is_ok());
+
+        headers.insert("x-amz-checksum-crc32c", "4waSgw==".parse().unwrap());
+        assert!(verify(&headers, b"123456789").is_ok());
+        let err = verify(&headers, b"12345678").unwrap_err();
+        assert!(matches!(
+            err,
+            S3ClientError::ChecksumMismatch { ref expected, .. } if expected == "4waSgw=="
+        ));
+
+        // Every checksum returned is verified
+        headers.insert(
+            "x-amz-checksum-sha256",
+            "FeKw08M4keuw8e9gnsQZQgwg4yDOlMZfvIwzEkSOsiU="
+                .parse()
+                .unwrap(),
+        );
+        assert!(verify(&headers, b"123456789").is_ok());
+        headers.insert("x-amz-checksum-sha256", "AAAA".parse().unwrap());
+        assert!(verify(&headers, b"123456789").is_err());
+        headers.remove("x-amz-checksum-sha256");
+
+        // Checksums of multipart uploads cover the parts, not the body
+        headers.insert("x-amz-checksum-crc32c", "AAAAAA==-3".parse().unwrap());
+        assert!(verify(&headers, b"12345678").is_ok());
+    }
+}
@@ -0,0 +1,342 @@
+//! Helpers for AWS S3 endpoints
+//!
+//! <https://docs.aws.amazon.com/general/latest/gr/s3.html>
+
+/// The region of the global endpoint `s3.amazonaws.com`
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// The regions with a FIPS endpoint
+const FIPS_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "ca-central-1",
+    "ca-west-1",
+    "us-gov-east-1",
+    "us-gov-west-1",
+];
+
+/// Returns the region embedded in a standard AWS S3 endpoint, e.g. `eu-west-1` for
+/// `s3.eu-west-1.amazonaws.com`, or `None` if `endpoint` is not an AWS endpoint or
+/// doesn't determine a region
+///
+/// The endpoint may include a scheme and port, as accepted by
+/// [`S3ClientBuilder::endpoint`](crate::client::S3ClientBuilder::endpoint)
+pub fn region_from_endpoint(endpoint: &str) -> Option<String> {
+    let host = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest);
+    let host = host.split(['/', ':']).next()?.to_ascii_lowercase();
+
+    let labels = host
+        .strip_suffix(".amazonaws.com")
+        .or_else(|| host.strip_suffix(".amazonaws.com.cn"))?
+        .split('.')
+        .collect::<Vec<_>>();
+
+    // Skip a bucket name in a virtual-hosted endpoint, `bucket.s3.region.amazonaws.com`
+    let service = labels
+        .iter()
+        .position(|label| *label == "s3" || label.starts_with("s3-"))?;
+
+    let region = labels[service + 1..]
+        .iter()
+        .rev()
+        .find(|label| **label != "dualstack");
+
+    match (labels[service], region) {
+        (_, Some(region)) => is_region(region).then(|| region.to_string()),
+        ("s3" | "s3-external-1", None) => Some(DEFAULT_REGION.to_string()),
+        // Legacy dash style, `s3-us-west-2.amazonaws.com`
+        (label, None) => {
+            let region = &label[3..];
+            is_region(region).then(|| region.to_string())
+        }
+    }
+}
+
+/// Returns `authority`, an AWS S3 endpoint with an optional bucket and port, changed to
+/// address `region`, or `None` if it is not an AWS endpoint
+pub(crate) fn authority_for_region(authority: &str, region: &str) -> Option<String> {
+    let current = region_from_endpoint(authority)?;
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+
+    // Only labels after the service are rewritten, as a bucket may look like a region
+    let mut labels = host.split('.').map(str::to_string).collect::<Vec<_>>();
+    let service = labels
+        .iter()
+        .position(|label| label.eq_ignore_ascii_case("s3") || label.starts_with("s3-"))?;
+    let global = !labels[service + 1..].iter().any(|l| is_region(l));
+
+    for label in &mut labels[service..] {
+        match label.to_ascii_lowercase() {
+            // The global endpoint, or the legacy dash style
+            l if l == "s3" && global => *label = format!("s3.{region}"),
+            l if l == "s3-external-1" || l == format!("s3-{current}") => {
+                *label = format!("s3.{region}")
+            }
+            l if l == current => *label = region.to_string(),
+            _ => {}
+        }
+    }
+
+    let host = labels.join(".");
+    Some(match port {
+        Some(port) => format!("{host}:{port}"),
+        None => host,
+    })
+}
+
+/// Returns the variant of `authority`, an AWS S3 endpoint with an optional port, for
+/// `region`, that is dual-stack (IPv4 and IPv6) and/or FIPS compliant if requested or
+/// already so, e.g. `s3-fips.dualstack.us-east-1.amazonaws.com`, or `None` if it is
+/// not an AWS endpoint
+///
+/// Whether `region` has a FIPS endpoint is checked by [`has_fips_endpoint`]
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/dual-stack-endpoints.html>
+pub(crate) fn variant_authority(
+    authority: &str,
+    region: &str,
+    dual_stack: bool,
+    fips: bool,
+) -> Option<String> {
+    region_from_endpoint(authority)?;
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host.to_ascii_lowercase(), Some(port)),
+        None => (authority.to_ascii_lowercase(), None),
+    };
+    let labels = host.split('.').collect::<Vec<_>>();
+
+    let suffix = match host.ends_with(".cn") {
+        true => "amazonaws.com.cn",
+        false => "amazonaws.com",
+    };
+    let service = match fips || labels.contains(&"s3-fips") {
+        true => "s3-fips",
+        false => "s3",
+    };
+
+    let host = match dual_stack || labels.contains(&"dualstack") {
+        true => format!("{service}.dualstack.{region}.{suffix}"),
+        false => format!("{service}.{region}.{suffix}"),
+    };
+    Some(match port {
+        Some(port) => format!("{host}:{port}"),
+        None => host,
+    })
+}
+
+/// Returns the endpoint of S3 Transfer Acceleration, optionally dual-stack, which is
+/// global and only supports virtual-hosted style addressing
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/transfer-acceleration.html>
+pub(crate) fn accelerate_authority(dual_stack: bool) -> &'static str {
+    match dual_stack {
+        true => "s3-accelerate.dualstack.amazonaws.com",
+        false => "s3-accelerate.amazonaws.com",
+    }
+}
+
+/// Returns whether S3 has a FIPS endpoint in `region`, which is only the case for
+/// regions in the United States and Canada
+///
+/// <https://aws.amazon.com/compliance/fips/>
+pub(crate) fn has_fips_endpoint(region: &str) -> bool {
+    FIPS_REGIONS.contains(&region)
+}
+
+/// Returns whether `label` looks like a region, e.g. `us-gov-west-1`
+fn is_region(label: &str) -> bool {
+    label.contains('-')
+        && label.ends_with(|c: char| c.is_ascii_digit())
+        && label
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_from_endpoint() {
+        let cases = [
+            ("s3.amazonaws.com", Some("us-east-1")),
+            ("https://s3.amazonaws.com", Some("us-east-1")),
+            ("s3-external-1.amazonaws.com", Some("us-east-1")),
+            ("s3.eu-west-1.amazonaws.com", Some("eu-west-1")),
+            ("https://s3.eu-west-1.amazonaws.com:443/", Some("eu-west-1")),
+            ("S3.EU-WEST-1.AMAZONAWS.COM", Some("eu-west-1")),
+            // Legacy dash style
+            ("s3-us-west-2.amazonaws.com", Some("us-west-2")),
+            ("s3-accelerate.amazonaws.com", None),
+            // Virtual-hosted style, including buckets that look like regions
+            ("bucket.s3.ap-south-1.amazonaws.com", Some("ap-south-1")),
+            ("us-west-1.s3.amazonaws.com", Some("us-east-1")),
+            (
+                "my.bucket.s3-eu-central-1.amazonaws.com",
+                Some("eu-central-1"),
+            ),
+            // Dualstack
+            ("s3.dualstack.us-east-2.amazonaws.com", Some("us-east-2")),
+            (
+                "bucket.s3.dualstack.sa-east-1.amazonaws.com",
+                Some("sa-east-1"),
+            ),
+            // China
+            ("s3.cn-north-1.amazonaws.com.cn", Some("cn-north-1")),
+            (
+                "s3.dualstack.cn-northwest-1.amazonaws.com.cn",
+                Some("cn-northwest-1"),
+            ),
+            ("s3.us-gov-west-1.amazonaws.com", Some("us-gov-west-1")),
+            // Not AWS S3
+            ("localhost:9000", None),
+            ("http://127.0.0.1:9000", None),
+            ("minio.example.com", None),
+            ("s3.example.com", None),
+            ("storage.googleapis.com", None),
+            ("sts.us-east-1.amazonaws.com", None),
+            ("s3.amazonaws.com.example.com", None),
+        ];
+
+        for (endpoint, expected) in cases {
+            assert_eq!(
+                region_from_endpoint(endpoint).as_deref(),
+                expected,
+                "{endpoint}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_authority_for_region() {
+        let cases = [
+            ("s3.amazonaws.com", Some("s3.eu-west-1.amazonaws.com")),
+            (
+                "s3-external-1.amazonaws.com",
+                Some("s3.eu-west-1.amazonaws.com"),
+            ),
+            (
+                "s3.us-west-2.amazonaws.com",
+                Some("s3.eu-west-1.amazonaws.com"),
+            ),
+            (
+                "s3-us-west-2.amazonaws.com",
+                Some("s3.eu-west-1.amazonaws.com"),
+            ),
+            (
+                "bucket.s3.us-west-2.amazonaws.com:443",
+                Some("bucket.s3.eu-west-1.amazonaws.com:443"),
+            ),
+            (
+                "s3.dualstack.us-east-1.amazonaws.com",
+                Some("s3.dualstack.eu-west-1.amazonaws.com"),
+            ),
+            // Bucket names are left alone
+            (
+                "us-west-2.s3.us-west-2.amazonaws.com",
+                Some("us-west-2.s3.eu-west-1.amazonaws.com"),
+            ),
+            ("localhost:9000", None),
+            ("minio.example.com", None),
+        ];
+
+        for (authority, expected) in cases {
+            assert_eq!(
+                authority_for_region(authority, "eu-west-1").as_deref(),
+                expected,
+                "{authority}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_variant_authority() {
+        let cases = [
+            (
+                "s3.amazonaws.com",
+                "us-east-1",
+                true,
+                false,
+                Some("s3.dualstack.us-east-1.amazonaws.com"),
+            ),
+            (
+                "s3.eu-west-1.amazonaws.com:443",
+                "eu-west-1",
+                true,
+                false,
+                Some("s3.dualstack.eu-west-1.amazonaws.com:443"),
+            ),
+            (
+                "s3.cn-north-1.amazonaws.com.cn",
+                "cn-north-1",
+                true,
+                false,
+                Some("s3.dualstack.cn-north-1.amazonaws.com.cn"),
+            ),
+            (
+                "s3.amazonaws.com",
+                "us-east-1",
+                false,
+                true,
+                Some("s3-fips.us-east-1.amazonaws.com"),
+            ),
+            (
+                "s3.us-west-2.amazonaws.com",
+                "us-west-2",
+                true,
+                true,
+                Some("s3-fips.dualstack.us-west-2.amazonaws.com"),
+            ),
+            // Variants of the endpoint are kept
+            (
+                "s3.dualstack.us-west-2.amazonaws.com",
+                "us-west-2",
+                false,
+                true,
+                Some("s3-fips.dualstack.us-west-2.amazonaws.com"),
+            ),
+            (
+                "s3-fips.us-east-2.amazonaws.com",
+                "us-east-2",
+                true,
+                false,
+                Some("s3-fips.dualstack.us-east-2.amazonaws.com"),
+            ),
+            ("localhost:9000", "us-east-1", true, false, None),
+            ("minio.example.com", "us-east-1", false, true, None),
+        ];
+
+        for (authority, region, dual_stack, fips, expected) in cases {
+            assert_eq!(
+                variant_authority(authority, region, dual_stack, fips).as_deref(),
+                expected,
+                "{authority}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_accelerate_authority() {
+        assert_eq!(accelerate_authority(false), "s3-accelerate.amazonaws.com");
+        assert_eq!(
+            accelerate_authority(true),
+            "s3-accelerate.dualstack.amazonaws.com"
+        );
+    }
+
+    #[test]
+    fn test_has_fips_endpoint() {
+        assert!(has_fips_endpoint("us-east-1"));
+        assert!(has_fips_endpoint("us-gov-west-1"));
+        assert!(has_fips_endpoint("ca-central-1"));
+        assert!(!has_fips_endpoint("eu-west-1"));
+        assert!(!has_fips_endpoint("ap-southeast-2"));
+    }
+}
@@ -1,8 +1,145 @@
+use hyper::StatusCode;
+use std::fmt::{self, Display, Formatter};
+
+mod s3_error;
+
+pub use s3_error::S3ErrorResponse;
+
 pub type Result<T, E = S3ClientError> = std::result::Result<T, E>;
 
+#[derive(Debug)]
 pub enum S3ClientError {
     HyperError(hyper::Error),
     HttpError(hyper::http::Error),
+    IoError(std::io::Error),
+    /// Connecting to S3 or waiting for its response took too long
+    Timeout(String),
+    /// No credentials could be found by a [`CredentialProvider`](crate::credentials::CredentialProvider)
+    CredentialsNotFound(String),
+    /// A byte range was empty or inverted
+    InvalidRange(String),
+    /// A credential contained characters that cannot be sent in a header
+    InvalidCredential(String),
+    /// A request URI could not be constructed or parsed
+    InvalidUri(String),
+    /// The client was configured incorrectly
+    InvalidConfiguration(String),
+    /// An argument passed to the client was invalid
+    InvalidArgument(String),
+    /// A request or response body could not be (de)serialized as XML
+    XmlError(quick_xml::DeError),
+    /// S3 returned a response that could not be interpreted
+    InvalidResponse(String),
+    /// The requested object does not exist
+    NotFound(String),
+    /// A restore of the archived object is already in progress
+    RestoreAlreadyInProgress(String),
+    /// A condition of the request, e.g. `If-Match`, did not hold
+    PreconditionFailed(String),
+    /// The checksum of a response body did not match the one returned by S3
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// A [`BlockingS3Client`](crate::blocking::BlockingS3Client) was called from
+    /// within an async runtime, where blocking would stall or deadlock it
+    BlockingInAsyncContext,
+    /// S3 responded with a non-success status code
+    ServiceError {
+        status: StatusCode,
+        response: Box<S3ErrorResponse>,
+    },
+}
+
+impl S3ClientError {
+    /// Returns the status of the response S3 rejected the request with, if any
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::ServiceError { status, .. } => Some(*status),
+            Self::NotFound(_) => Some(StatusCode::NOT_FOUND),
+            Self::PreconditionFailed(_) => Some(StatusCode::PRECONDITION_FAILED),
+            Self::RestoreAlreadyInProgress(_) => Some(StatusCode::CONFLICT),
+            _ => None,
+        }
+    }
+
+    /// Creates a [`S3ClientError::ServiceError`] from an unsuccessful response,
+    /// where `body` is the (possibly empty) XML error document, or a
+    /// [`S3ClientError::PreconditionFailed`] for `412 Precondition Failed`
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        let response = S3ErrorResponse::parse(body).unwrap_or_else(|| S3ErrorResponse {
+            code: status.canonical_reason().unwrap_or("Unknown").to_string(),
+            message: String::from_utf8_lossy(body).into_owned(),
+            request_id: None,
+            resource: None,
+            region: None,
+            server_time: None,
+        });
+
+        if status == StatusCode::PRECONDITION_FAILED {
+            return Self::PreconditionFailed(match response.message.is_empty() {
+                true => response.code,
+                false => response.message,
+            });
+        }
+
+        Self::ServiceError {
+            status,
+            response: Box::new(response),
+        }
+    }
+}
+
+impl Display for S3ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HyperError(e) => write!(f, "HTTP transport error: {e}"),
+            Self::HttpError(e) => write!(f, "invalid HTTP request: {e}"),
+            Self::IoError(e) => write!(f, "IO error: {e}"),
+            Self::Timeout(msg) => write!(f, "timed out: {msg}"),
+            Self::CredentialsNotFound(msg) => write!(f, "credentials not found: {msg}"),
+            Self::InvalidRange(msg) => write!(f, "invalid range: {msg}"),
+            Self::InvalidCredential(msg) => write!(f, "invalid credential: {msg}"),
+            Self::InvalidUri(msg) => write!(f, "invalid URI: {msg}"),
+            Self::InvalidConfiguration(msg) => write!(f, "invalid configuration: {msg}"),
+            Self::InvalidArgument(msg) => write!(f, "invalid argument: {msg}"),
+            Self::XmlError(e) => write!(f, "invalid XML: {e}"),
+            Self::InvalidResponse(msg) => write!(f, "invalid response: {msg}"),
+            Self::NotFound(path) => write!(f, "object not found: {path}"),
+            Self::RestoreAlreadyInProgress(path) => {
+                write!(f, "restore already in progress: {path}")
+            }
+            Self::PreconditionFailed(msg) => write!(f, "precondition failed: {msg}"),
+            Self::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch: expected {expected}, got {actual}")
+            }
+            Self::BlockingInAsyncContext => {
+                write!(f, "blocking client called from within an async runtime")
+            }
+            Self::ServiceError { status, response } => {
+                write!(f, "S3 returned {status}: {}", response.code)?;
+                if !response.message.is_empty() {
+                    write!(f, ": {}", response.message)?;
+                }
+                if let Some(request_id) = &response.request_id {
+                    write!(f, " (request id {request_id})")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for S3ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::HyperError(e) => Some(e),
+            Self::HttpError(e) => Some(e),
+            Self::IoError(e) => Some(e),
+            Self::XmlError(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl From<hyper::Error> for S3ClientError {
@@ -16,3 +153,63 @@ impl From<hyper::http::Error> for S3ClientError {
         Self::HttpError(value)
     }
 }
+
+impl From<std::io::Error> for S3ClientError {
+    fn from(value: std::io::Error) -> Self {
+        Self::IoError(value)
+    }
+}
+
+impl From<quick_xml::DeError> for S3ClientError {
+    fn from(value: quick_xml::DeError) -> Self {
+        Self::XmlError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[tokio::test]
+    async fn test_display_and_source() {
+        let stream = futures::stream::once(async {
+            Err::<bytes::Bytes, _>(std::io::Error::other("stream failed"))
+        });
+        let hyper_error = hyper::body::to_bytes(hyper::Body::wrap_stream(stream))
+            .await
+            .unwrap_err();
+        let http_error = hyper::Request::get("\n").body(()).unwrap_err();
+        let io_error = std::io::Error::other("disk on fire");
+
+        let with_source = [
+            S3ClientError::HyperError(hyper_error),
+            S3ClientError::HttpError(http_error),
+            S3ClientError::IoError(io_error),
+        ];
+        for error in &with_source {
+            assert!(!error.to_string().is_empty());
+            let source = error.source().expect("source");
+            assert!(error.to_string().contains(&source.to_string()), "{error}");
+        }
+
+        let without_source = [
+            S3ClientError::CredentialsNotFound("AWS_ACCESS_KEY_ID not set".into()),
+            S3ClientError::InvalidConfiguration("region must be configured".into()),
+            S3ClientError::from_response(
+                StatusCode::NOT_FOUND,
+                b"<Error><Code>NoSuchKey</Code><Message>Not found</Message>\
+                  <RequestId>4442587FB7D0A2F9</RequestId></Error>",
+            ),
+        ];
+        for error in &without_source {
+            assert!(!error.to_string().is_empty());
+            assert!(error.source().is_none());
+        }
+
+        assert_eq!(
+            without_source[2].to_string(),
+            "S3 returned 404 Not Found: NoSuchKey: Not found (request id 4442587FB7D0A2F9)"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Deserialize;
+
+/// The XML error document returned by S3 for unsuccessful requests
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/API/ErrorResponses.html>
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3ErrorResponse {
+    /// The error code, e.g. `NoSuchKey`
+    pub code: String,
+    /// A human readable description of the error
+    #[serde(default)]
+    pub message: String,
+    /// The id of the request that failed
+    pub request_id: Option<String>,
+    /// The bucket or object involved in the error
+    pub resource: Option<String>,
+    /// The region of the bucket, if the request was sent to the wrong region
+    pub region: Option<String>,
+    /// The time of the server, if the request was rejected as `RequestTimeTooSkewed`
+    pub server_time: Option<String>,
+}
+
+impl S3ErrorResponse {
+    /// Parses an S3 XML error document, returning `None` if `body` is not one
+    pub fn parse(body: &[u8]) -> Option<Self> {
+        quick_xml::de::from_reader(body).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_no_such_key() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>NoSuchKey</Code>
+  <Message>The resource you requested does not exist</Message>
+  <Resource>/mybucket/myfoto.jpg</Resource>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#;
+
+        assert_eq!(
+            S3ErrorResponse::parse(body).unwrap(),
+            S3ErrorResponse {
+                code: "NoSuchKey".into(),
+                message: "The resource you requested does not exist".into(),
+                request_id: Some("4442587FB7D0A2F9".into()),
+                resource: Some("/mybucket/myfoto.jpg".into()),
+                region: None,
+                server_time: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_access_denied() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error><Code>AccessDenied</Code><Message>Access Denied</Message><RequestId>656c76696e6727732072657175657374</RequestId><HostId>Uuag1LuByRx9e6j5Onimru9pO4ZVKnJ2Qz7/C1NPcfTWAtRPfTaOFg==</HostId></Error>"#;
+
+        assert_eq!(
+            S3ErrorResponse::parse(body).unwrap(),
+            S3ErrorResponse {
+                code: "AccessDenied".into(),
+                message: "Access Denied".into(),
+                request_id: Some("656c76696e6727732072657175657374".into()),
+                resource: None,
+                region: None,
+                server_time: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_request_time_too_skewed() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error><Code>RequestTimeTooSkewed</Code><Message>The difference between the request time and the current time is too large.</Message><RequestTime>20130524T000000Z</RequestTime><ServerTime>2024-01-01T12:00:00Z</ServerTime><MaxAllowedSkewMilliseconds>900000</MaxAllowedSkewMilliseconds><RequestId>4442587FB7D0A2F9</RequestId></Error>"#;
+
+        let response = S3ErrorResponse::parse(body).unwrap();
+        assert_eq!(response.code, "RequestTimeTooSkewed");
+        assert_eq!(
+            response.server_time.as_deref(),
+            Some("2024-01-01T12:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(S3ErrorResponse::parse(b""), None);
+        assert_eq!(S3ErrorResponse::parse(b"not xml"), None);
+        assert_eq!(
+            S3ErrorResponse::parse(b"<Error><Message>m</Message></Error>"),
+            None
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Decoding of `application/vnd.amazon.eventstream` response bodies, as returned by
+//! `SelectObjectContent`
+//!
+//! Each message is framed by its length and protected by CRC-32 checksums of its
+//! prelude and of the whole message
+//!
+//! <https://docs.aws.amazon.com/AmazonS3/latest/API/RESTSelectObjectAppendix.html>
+
+use crate::checksum::crc32;
+use crate::error::{Result, S3ClientError, S3ErrorResponse};
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{stream, Stream, StreamExt};
+use hyper::{Body, StatusCode};
+
+/// The length of the prelude: the total length, the length of the headers and a CRC
+const PRELUDE_LEN: usize = 12;
+
+/// The length of the CRC of the whole message, which ends it
+const MESSAGE_CRC_LEN: usize = 4;
+
+/// The type of string header values, the only type S3 sends
+const STRING_HEADER: u8 = 7;
+
+/// A message of an event stream
+pub(crate) struct Message {
+    pub headers: Vec<(String, String)>,
+    pub payload: Bytes,
+}
+
+impl Message {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> S3ClientError {
+    S3ClientError::InvalidResponse(format!("invalid event stream: {}", msg.into()))
+}
+
+/// Splits `n` bytes off the front of `buf`, failing if it is shorter
+fn take(buf: &mut Bytes, n: usize) -> Result<Bytes> {
+    match buf.len() >= n {
+        true => Ok(buf.split_to(n)),
+        false => Err(invalid("truncated header")),
+    }
+}
+
+/// Decodes the first message of `buf`, or returns `None` if `buf` doesn't contain
+/// all of it yet
+pub(crate) fn decode_message(buf: &mut BytesMut) -> Result<Option<Message>> {
+    if buf.len() < PRELUDE_LEN {
+        return Ok(None);
+    }
+    let mut prelude = &buf[..PRELUDE_LEN];
+    let total_len = prelude.get_u32() as usize;
+    let headers_len = prelude.get_u32() as usize;
+    if prelude.get_u32() != crc32(&buf[..8]) {
+        return Err(invalid("prelude checksum mismatch"));
+    }
+    if total_len < PRELUDE_LEN + headers_len + MESSAGE_CRC_LEN {
+        return Err(invalid(format!("message length {total_len} is too short")));
+    }
+    if buf.len() < total_len {
+        return Ok(None);
+    }
+
+    let mut message = buf.split_to(total_len).freeze();
+    let (body, mut message_crc) = message.split_at(total_len - MESSAGE_CRC_LEN);
+    if message_crc.get_u32() != crc32(body) {
+        return Err(invalid("message checksum mismatch"));
+    }
+
+    message.advance(PRELUDE_LEN);
+    let headers = decode_headers(message.split_to(headers_len))?;
+    message.truncate(message.len() - MESSAGE_CRC_LEN);
+
+    Ok(Some(Message {
+        headers,
+        payload: message,
+    }))
+}
+
+fn decode_headers(mut buf: Bytes) -> Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    while buf.has_remaining() {
+        let name_len = take(&mut buf, 1)?.get_u8() as usize;
+        let name = take(&mut buf, name_len)?;
+        if take(&mut buf, 1)?.get_u8() != STRING_HEADER {
+            return Err(invalid("unsupported header value type"));
+        }
+        let value_len = take(&mut buf, 2)?.get_u16() as usize;
+        let value = take(&mut buf, value_len)?;
+
+        let string = |bytes: Bytes| {
+            String::from_utf8(bytes.to_vec()).map_err(|_| invalid("header is not UTF-8"))
+        };
+        headers.push((string(name)?, string(value)?));
+    }
+    Ok(headers)
+}
+
+/// Returns the payloads of the `Records` events of a `SelectObjectContent` response,
+/// skipping the `Stats`, `Progress` and `Cont` events
+///
+/// Fails if S3 sends an error message, or if the body ends before the `End` event,
+/// in which case the query did not complete and the records may be incomplete
+pub(crate) fn records(body: Body) -> impl Stream<Item = Result<Bytes>> {
+    stream::try_unfold((body, BytesMut::new()), |(mut body, mut buf)| async move {
+        loop {
+            while let Some(message) = decode_message(&mut buf)? {
+                if message.header(":message-type") == Some("error") {
+                    return Err(S3ClientError::ServiceError {
+                        status: StatusCode::OK,
+                        response: Box::new(S3ErrorResponse {
+                            code: message.header(":error-code").unwrap_or_default().into(),
+                            message: message.header(":error-message").unwrap_or_default().into(),
+                            request_id: None,
+                            resource: None,
+                            region: None,
+                            server_time: None,
+                        }),
+                    });
+                }
+
+                match message.header(":event-type") {
+                    Some("Records") => return Ok(Some((message.payload, (body, buf)))),
+                    Some("End") => return Ok(None),
+                    _ => {}
+                }
+            }
+
+            match body.next().await {
+                Some(chunk) => buf.extend_from_slice(&chunk?),
+                None => return Err(invalid("body ended before the End event")),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+
+    /// Encodes an event message with string `headers`
+    fn encode(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut encoded_headers = Vec::new();
+        for (name, value) in headers {
+            encoded_headers.put_u8(name.len() as u8);
+            encoded_headers.put_slice(name.as_bytes());
+            encoded_headers.put_u8(STRING_HEADER);
+            encoded_headers.put_u16(value.len() as u16);
+            encoded_headers.put_slice(value.as_bytes());
+        }
+
+        let total_len = PRELUDE_LEN + encoded_headers.len() + payload.len() + MESSAGE_CRC_LEN;
+        let mut message = Vec::with_capacity(total_len);
+        message.put_u32(total_len as u32);
+        message.put_u32(encoded_headers.len() as u32);
+        message.put_u32(crc32(&message));
+        message.put_slice(&encoded_headers);
+        message.put_slice(payload);
+        message.put_u32(crc32(&message));
+        message
+    }
+
+    fn event(event_type: &str, payload: &[u8]) -> Vec<u8> {
+        encode(
+            &[(":message-type", "event"), (":event-type", event_type)],
+            payload,
+        )
+    }
+
+    async fn collect(chunks: Vec<Vec<u8>>) -> Vec<Result<Bytes>> {
+        let chunks = chunks.into_iter().map(Ok::<_, std::io::Error>);
+        records(Body::wrap_stream(stream::iter(chunks)))
+            .collect()
+            .await
+    }
+
+    /// A `SelectObjectContent` response body of two `Records` events separated by a
+    /// `Cont` event, followed by the `Stats` and `End` events
+    const CAPTURED: &[u8] = include_bytes!("../testdata/select_records.eventstream");
+
+    #[test]
+    fn test_decode_message() {
+        let mut buf = BytesMut::from(CAPTURED);
+        let message = decode_message(&mut buf).unwrap().unwrap();
+        assert_eq!(
+            message.headers,
+            [
+                (":event-type".to_string(), "Records".to_string()),
+                (
+                    ":content-type".to_string(),
+                    "application/octet-stream".to_string()
+                ),
+                (":message-type".to_string(), "event".to_string()),
+            ]
+        );
+        assert_eq!(message.payload, "1,Sam\n2,Jeff\n");
+
+        let mut events = vec![];
+        while let Some(message) = decode_message(&mut buf).unwrap() {
+            events.push(message.header(":event-type").unwrap().to_string());
+        }
+        assert_eq!(events, ["Cont", "Records", "Stats", "End"]);
+        assert!(buf.is_empty());
+
+        // Incomplete messages are left in the buffer until the rest arrives
+        let mut buf = BytesMut::from(&CAPTURED[..20]);
+        assert!(decode_message(&mut buf).unwrap().is_none());
+        assert_eq!(buf.len(), 20);
+
+        let mut corrupted = BytesMut::from(CAPTURED);
+        corrupted[20] ^= 1;
+        let err = decode_message(&mut corrupted).err().unwrap();
+        assert!(
+            matches!(&err, S3ClientError::InvalidResponse(msg) if msg.contains("message checksum mismatch")),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_captured_records() {
+        // Delivered a few bytes at a time, splitting messages at arbitrary points
+        let chunks = CAPTURED.chunks(7).map(<[u8]>::to_vec).collect();
+        let records = collect(chunks).await;
+        let records = records.into_iter().map(|r| r.unwrap()).collect::<Vec<_>>();
+        assert_eq!(records, ["1,Sam\n2,Jeff\n", "3,Ana\n"]);
+    }
+
+    #[tokio::test]
+    async fn test_records() {
+        // Records, split across two chunks in the middle of the second message
+        let first = event("Records", b"a,1\n");
+        let second = event("Records", b"b,2\n");
+        let stats = event("Stats", b"<Stats></Stats>");
+        let (head, tail) = second.split_at(7);
+        let chunks = vec![
+            [first.as_slice(), head].concat(),
+            [tail, &stats, &event("End", b"")].concat(),
+        ];
+        let records = collect(chunks).await;
+        let records = records.into_iter().map(|r| r.unwrap()).collect::<Vec<_>>();
+        assert_eq!(records, ["a,1\n", "b,2\n"]);
+
+        // A corrupted prelude checksum
+        let mut corrupted = event("Records", b"a,1\n");
+        corrupted[8] ^= 1;
+        let records = collect(vec![corrupted]).await;
+        assert_eq!(records.len(), 1);
+        assert!(
+            matches!(&records[0], Err(S3ClientError::InvalidResponse(msg))
+            if msg.contains("prelude checksum mismatch"))
+        );
+
+        // No End event
+        let records = collect(vec![event("Records", b"a,1\n")]).await;
+        assert_eq!(records.len(), 2);
+        assert!(records[1].is_err());
+
+        // An error message
+        let error = encode(
+            &[
+                (":message-type", "error"),
+                (":error-code", "InternalError"),
+                (":error-message", "failed"),
+            ],
+            b"",
+        );
+        let records = collect(vec![error]).await;
+        assert!(
+            matches!(&records[0], Err(S3ClientError::ServiceError { response, .. })
+            if response.code == "InternalError" && response.message == "failed")
+        );
+    }
+}
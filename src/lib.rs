@@ -1,6 +1,19 @@
+pub mod blocking;
+pub mod checksum;
+mod chunked;
 pub mod client;
-mod credentials;
+pub mod credentials;
+pub mod endpoint;
 pub mod error;
+mod event_stream;
 pub mod linux;
+pub mod metrics;
+mod mime;
+pub mod reader;
+pub mod retry;
+pub mod store;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod token;
 pub mod tokio;
+pub mod types;
@@ -0,0 +1,214 @@
+//! Hooks for exporting metrics of the requests sent to S3, e.g. to Prometheus or statsd
+
+use crate::client::COPY_SOURCE_HEADER;
+use hyper::{HeaderMap, Method, StatusCode};
+use std::time::Duration;
+
+/// Receives the metrics of every request sent by an
+/// [`S3Client`](crate::client::S3Client), configured with
+/// [`S3ClientBuilder::metrics`](crate::client::S3ClientBuilder::metrics)
+///
+/// It is called on the request path, so should only update counters or the like
+pub trait MetricsSink: Send + Sync {
+    fn record(&self, metrics: &RequestMetrics);
+}
+
+/// The metrics of a single request, including any retries of it
+#[derive(Debug, Clone)]
+pub struct RequestMetrics {
+    /// The S3 API called, e.g. `GetObject`, or `Unknown` for requests sent with
+    /// [`S3Client::send`](crate::client::S3Client::send) to APIs the client doesn't know
+    pub operation: &'static str,
+    /// The length of the request body
+    pub bytes_sent: u64,
+    /// The `Content-Length` of the response, whose body may still be streamed once
+    /// the request is recorded
+    pub bytes_received: Option<u64>,
+    /// The status of the final response, or `None` if no response was received
+    pub status: Option<StatusCode>,
+    /// The time until the response headers were received
+    pub duration: Duration,
+}
+
+/// A [`MetricsSink`] that discards all metrics, the default
+#[derive(Debug, Default)]
+pub struct NoopMetrics;
+
+impl MetricsSink for NoopMetrics {
+    fn record(&self, _metrics: &RequestMetrics) {}
+}
+
+/// Returns the name of the S3 API a request calls, derived from its method, whether it
+/// addresses a bucket and key, and the subresource in its `query`
+pub(crate) fn operation_name(
+    method: &Method,
+    bucket: &str,
+    key: &str,
+    query: &str,
+    headers: &HeaderMap,
+) -> &'static str {
+    let has = |name: &str| {
+        query
+            .split('&')
+            .any(|pair| pair.split('=').next() == Some(name))
+    };
+
+    match (method.as_str(), bucket.is_empty(), key.is_empty()) {
+        ("GET", true, _) => "ListBuckets",
+        ("GET", false, true) if has("location") => "GetBucketLocation",
+        ("GET", false, true) if has("list-type") => "ListObjectsV2",
+        ("GET", false, true) if has("versions") => "ListObjectVersions",
+        ("GET", false, true) if has("versioning") => "GetBucketVersioning",
+        ("PUT", false, true) if has("versioning") => "PutBucketVersioning",
+        ("HEAD", false, true) => "HeadBucket",
+        ("POST", false, true) if has("delete") => "DeleteObjects",
+        (_, false, true) => "Unknown",
+        ("GET", _, _) if has("tagging") => "GetObjectTagging",
+        ("PUT", _, _) if has("tagging") => "PutObjectTagging",
+        ("GET", _, _) if has("acl") => "GetObjectAcl",
+        ("PUT", _, _) if has("acl") => "PutObjectAcl",
+        ("GET", _, _) if has("retention") => "GetObjectRetention",
+        ("PUT", _, _) if has("retention") => "PutObjectRetention",
+        ("GET", _, _) if has("attributes") => "GetObjectAttributes",
+        ("POST", _, _) if has("restore") => "RestoreObject",
+        ("POST", _, _) if has("select") => "SelectObjectContent",
+        ("POST", _, _) if has("uploads") => "CreateMultipartUpload",
+        ("PUT", _, _) if has("partNumber") => "UploadPart",
+        ("POST", _, _) if has("uploadId") => "CompleteMultipartUpload",
+        ("DELETE", _, _) if has("uploadId") => "AbortMultipartUpload",
+        ("GET", _, _) => "GetObject",
+        ("HEAD", _, _) => "HeadObject",
+        ("PUT", _, _) if headers.contains_key(COPY_SOURCE_HEADER) => "CopyObject",
+        ("PUT", _, _) => "PutObject",
+        ("DELETE", _, _) => "DeleteObject",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn test_operation_name() {
+        let cases = [
+            (Method::GET, "", "", "", "ListBuckets"),
+            (Method::GET, "bucket", "", "location", "GetBucketLocation"),
+            (
+                Method::GET,
+                "bucket",
+                "",
+                "list-type=2&prefix=a",
+                "ListObjectsV2",
+            ),
+            (
+                Method::GET,
+                "bucket",
+                "",
+                "versions&key-marker=a",
+                "ListObjectVersions",
+            ),
+            (
+                Method::GET,
+                "bucket",
+                "",
+                "versioning",
+                "GetBucketVersioning",
+            ),
+            (
+                Method::PUT,
+                "bucket",
+                "",
+                "versioning",
+                "PutBucketVersioning",
+            ),
+            (Method::HEAD, "bucket", "", "", "HeadBucket"),
+            (Method::POST, "bucket", "", "delete", "DeleteObjects"),
+            (Method::PUT, "bucket", "", "", "Unknown"),
+            (Method::GET, "bucket", "key", "", "GetObject"),
+            (Method::HEAD, "bucket", "key", "", "HeadObject"),
+            (Method::PUT, "bucket", "key", "", "PutObject"),
+            (Method::DELETE, "bucket", "key", "", "DeleteObject"),
+            (Method::GET, "bucket", "key", "tagging", "GetObjectTagging"),
+            (Method::PUT, "bucket", "key", "tagging", "PutObjectTagging"),
+            (Method::GET, "bucket", "key", "acl", "GetObjectAcl"),
+            (Method::PUT, "bucket", "key", "acl", "PutObjectAcl"),
+            (
+                Method::GET,
+                "bucket",
+                "key",
+                "retention",
+                "GetObjectRetention",
+            ),
+            (
+                Method::PUT,
+                "bucket",
+                "key",
+                "retention",
+                "PutObjectRetention",
+            ),
+            (
+                Method::GET,
+                "bucket",
+                "key",
+                "attributes",
+                "GetObjectAttributes",
+            ),
+            (Method::POST, "bucket", "key", "restore", "RestoreObject"),
+            (
+                Method::POST,
+                "bucket",
+                "key",
+                "select&select-type=2",
+                "SelectObjectContent",
+            ),
+            (
+                Method::POST,
+                "bucket",
+                "key",
+                "uploads",
+                "CreateMultipartUpload",
+            ),
+            (
+                Method::PUT,
+                "bucket",
+                "key",
+                "partNumber=1&uploadId=abc",
+                "UploadPart",
+            ),
+            (
+                Method::POST,
+                "bucket",
+                "key",
+                "uploadId=abc",
+                "CompleteMultipartUpload",
+            ),
+            (
+                Method::DELETE,
+                "bucket",
+                "key",
+                "uploadId=abc",
+                "AbortMultipartUpload",
+            ),
+            // Only the names of query parameters select the API, not their values
+            (Method::GET, "bucket", "key", "versionId=acl", "GetObject"),
+            (Method::PATCH, "bucket", "key", "", "Unknown"),
+        ];
+
+        for (method, bucket, key, query, expected) in cases {
+            assert_eq!(
+                operation_name(&method, bucket, key, query, &HeaderMap::new()),
+                expected,
+                "{method} {bucket}/{key}?{query}"
+            );
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(COPY_SOURCE_HEADER, HeaderValue::from_static("src/key"));
+        assert_eq!(
+            operation_name(&Method::PUT, "bucket", "key", "", &headers),
+            "CopyObject"
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Inference of the `Content-Type` of an object from the extension of its key, see
+//! [`S3ClientBuilder::detect_content_type`](crate::client::S3ClientBuilder::detect_content_type)
+
+/// The content type of keys without a known extension
+pub(crate) const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Content types of common file extensions, in lowercase
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("7z", "application/x-7z-compressed"),
+    ("avif", "image/avif"),
+    ("avro", "application/avro"),
+    ("bmp", "image/bmp"),
+    ("bz2", "application/x-bzip2"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("flac", "audio/flac"),
+    ("gif", "image/gif"),
+    ("gz", "application/gzip"),
+    ("htm", "text/html"),
+    ("html", "text/html"),
+    ("ico", "image/vnd.microsoft.icon"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("jsonl", "application/jsonl"),
+    ("md", "text/markdown"),
+    ("mjs", "text/javascript"),
+    ("mov", "video/quicktime"),
+    ("mp3", "audio/mpeg"),
+    ("mp4", "video/mp4"),
+    ("ogg", "audio/ogg"),
+    ("otf", "font/otf"),
+    ("parquet", "application/vnd.apache.parquet"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("svg", "image/svg+xml"),
+    ("tar", "application/x-tar"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("ttf", "font/ttf"),
+    ("txt", "text/plain"),
+    ("wasm", "application/wasm"),
+    ("wav", "audio/wav"),
+    ("webm", "video/webm"),
+    ("webp", "image/webp"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("xml", "application/xml"),
+    ("xz", "application/x-xz"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("zip", "application/zip"),
+    ("zst", "application/zstd"),
+];
+
+/// Returns the content type of `key` inferred from its extension, e.g. `image/png`
+/// for `photos/cat.PNG`, or [`DEFAULT_CONTENT_TYPE`] if it has no known extension
+pub(crate) fn content_type_for_key(key: &str) -> &'static str {
+    let name = key.rsplit('/').next().unwrap_or(key);
+    // The leading dot of e.g. `.profile` doesn't start an extension
+    let extension = match name.trim_start_matches('.').rsplit_once('.') {
+        Some((_, extension)) => extension.to_ascii_lowercase(),
+        None => return DEFAULT_CONTENT_TYPE,
+    };
+
+    CONTENT_TYPES
+        .binary_search_by_key(&extension.as_str(), |(extension, _)| extension)
+        .map_or(DEFAULT_CONTENT_TYPE, |idx| CONTENT_TYPES[idx].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_for_key() {
+        assert!(CONTENT_TYPES.windows(2).all(|w| w[0].0 < w[1].0));
+
+        assert_eq!(content_type_for_key("photo.png"), "image/png");
+        assert_eq!(content_type_for_key("photos/cat.PNG"), "image/png");
+        assert_eq!(content_type_for_key("archive.tar.gz"), "application/gzip");
+        assert_eq!(content_type_for_key("data"), DEFAULT_CONTENT_TYPE);
+        assert_eq!(content_type_for_key("data.unknown"), DEFAULT_CONTENT_TYPE);
+        assert_eq!(content_type_for_key(".profile"), DEFAULT_CONTENT_TYPE);
+        assert_eq!(content_type_for_key("dir.d/file"), DEFAULT_CONTENT_TYPE);
+        assert_eq!(DEFAULT_CONTENT_TYPE, "application/octet-stream");
+    }
+}
@@ -0,0 +1,75 @@
+use bytes::{Buf, Bytes};
+use futures::{ready, Stream};
+use hyper::Body;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// An [`AsyncRead`] over the body of an object, as returned by
+/// [`S3Client::get_reader`](crate::client::S3Client::get_reader)
+pub struct GetObjectReader {
+    body: Body,
+    /// The unread remainder of the last chunk received from `body`
+    chunk: Bytes,
+}
+
+impl GetObjectReader {
+    pub(crate) fn new(body: Body) -> Self {
+        Self {
+            body,
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl AsyncRead for GetObjectReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        loop {
+            if !self.chunk.is_empty() {
+                let len = self.chunk.len().min(buf.remaining());
+                buf.put_slice(&self.chunk[..len]);
+                self.chunk.advance(len);
+                return Poll::Ready(Ok(()));
+            }
+
+            match ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(chunk)) => self.chunk = chunk,
+                Some(Err(e)) => return Poll::Ready(Err(std::io::Error::other(e))),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_read_with_small_buffer() {
+        let chunks = ["hello ", "", "wonderful", " world", "!"];
+        let body = Body::wrap_stream(stream::iter(
+            chunks.map(|chunk| Ok::<_, std::io::Error>(Bytes::from(chunk))),
+        ));
+        let mut reader = GetObjectReader::new(body);
+
+        let mut read = Vec::new();
+        let mut buf = [0; 3];
+        loop {
+            let n = reader.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            assert!(n <= buf.len());
+            read.extend_from_slice(&buf[..n]);
+        }
+
+        assert_eq!(read, chunks.concat().as_bytes());
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use hyper::header::RETRY_AFTER;
+use hyper::{HeaderMap, Method, StatusCode};
+use rand::Rng;
+use std::time::Duration;
+
+/// Configures how requests that fail with a transient error are retried
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// The maximum number of times a request is attempted, including the first attempt
+    pub max_attempts: usize,
+    /// The delay before the first retry, doubled on each subsequent retry
+    pub base_delay: Duration,
+    /// The maximum delay between retries, unless a longer one is requested by the
+    /// `Retry-After` header of the response
+    pub max_delay: Duration,
+    /// Whether requests that are not idempotent, such as `PUT` and `POST`, are retried
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns whether a request with `method` may be retried
+    pub(crate) fn should_retry(&self, method: &Method) -> bool {
+        let idempotent = matches!(*method, Method::GET | Method::HEAD | Method::DELETE);
+        idempotent || self.retry_non_idempotent
+    }
+
+    /// Returns the delay before retry number `retry`, starting at 1, using
+    /// exponential backoff with full jitter
+    pub(crate) fn backoff(&self, retry: usize) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31) as u32;
+        let delay = self
+            .base_delay
+            .saturating_mul(2_u32.pow(exponent))
+            .min(self.max_delay);
+
+        delay.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// Returns the delay requested by the `Retry-After` header of a response, given either
+/// in seconds or as an HTTP date
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Returns whether a response with `status` indicates a transient error
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::SERVICE_UNAVAILABLE
+    )
+}
+
+/// Returns whether `error` is a connection level error that may succeed if retried
+pub(crate) fn is_retryable_error(error: &hyper::Error) -> bool {
+    error.is_connect() || error.is_closed() || error.is_incomplete_message()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let config = RetryConfig {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(250),
+            ..Default::default()
+        };
+
+        for _ in 0..100 {
+            assert!(config.backoff(1) <= Duration::from_millis(100));
+            assert!(config.backoff(2) <= Duration::from_millis(200));
+            assert!(config.backoff(3) <= Duration::from_millis(250));
+            assert!(config.backoff(usize::MAX) <= Duration::from_millis(250));
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let config = RetryConfig::default();
+        assert!(config.should_retry(&Method::GET));
+        assert!(config.should_retry(&Method::DELETE));
+        assert!(!config.should_retry(&Method::PUT));
+        assert!(!config.should_retry(&Method::POST));
+
+        let config = RetryConfig {
+            retry_non_idempotent: true,
+            ..Default::default()
+        };
+        assert!(config.should_retry(&Method::PUT));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, value.parse().unwrap());
+            headers
+        };
+
+        assert_eq!(retry_after(&headers("5")), Some(Duration::from_secs(5)));
+        assert_eq!(retry_after(&headers(" 0 ")), Some(Duration::ZERO));
+
+        let date = Utc::now() + chrono::Duration::seconds(30);
+        let delay = retry_after(&headers(&date.to_rfc2822())).unwrap();
+        assert!(delay > Duration::from_secs(28), "{delay:?}");
+        assert!(delay <= Duration::from_secs(30), "{delay:?}");
+
+        // A date in the past asks for no delay
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+
+        assert_eq!(retry_after(&headers("soon")), None);
+        assert_eq!(retry_after(&headers("-1")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::client::S3Client;
+use crate::error::Result;
+use crate::types::{ByteRange, ListObjectsV2Output, ObjectMetadata, PutObjectOutput};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use hyper::client::connect::Connect;
+
+/// The core object operations of [`S3Client`], so that code using them can depend
+/// on `dyn ObjectStore` and be tested against a fake implementation
+pub trait ObjectStore: Send + Sync {
+    /// See [`S3Client::get_object`]
+    fn get<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        range: Option<ByteRange>,
+    ) -> BoxFuture<'a, Result<Bytes>>;
+
+    /// See [`S3Client::put_object`]
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        body: Bytes,
+    ) -> BoxFuture<'a, Result<PutObjectOutput>>;
+
+    /// See [`S3Client::head_object`]
+    fn head_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectMetadata>>;
+
+    /// See [`S3Client::delete_object`]
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// See [`S3Client::list_objects_v2`]
+    fn list_objects_v2<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: Option<&'a str>,
+        continuation_token: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<ListObjectsV2Output>>;
+}
+
+impl<S: Connect + Clone + Send + Sync + 'static> ObjectStore for S3Client<S> {
+    fn get<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        range: Option<ByteRange>,
+    ) -> BoxFuture<'a, Result<Bytes>> {
+        Box::pin(async move { Ok(self.get_object(bucket, key, range).await?.body) })
+    }
+
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        body: Bytes,
+    ) -> BoxFuture<'a, Result<PutObjectOutput>> {
+        Box::pin(S3Client::put_object(self, bucket, key, body))
+    }
+
+    fn head_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectMetadata>> {
+        Box::pin(S3Client::head_object(self, bucket, key))
+    }
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(S3Client::delete_object(self, bucket, key))
+    }
+
+    fn list_objects_v2<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: Option<&'a str>,
+        continuation_token: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<ListObjectsV2Output>> {
+        Box::pin(S3Client::list_objects_v2(
+            self,
+            bucket,
+            prefix,
+            continuation_token,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::S3ClientError;
+    use crate::types::ObjectInfo;
+    use chrono::Utc;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    /// A fake store keeping objects in memory
+    #[derive(Default)]
+    struct FakeStore {
+        objects: Mutex<BTreeMap<String, Bytes>>,
+    }
+
+    impl ObjectStore for FakeStore {
+        fn get<'a>(
+            &'a self,
+            bucket: &'a str,
+            key: &'a str,
+            _range: Option<ByteRange>,
+        ) -> BoxFuture<'a, Result<Bytes>> {
+            let path = format!("{bucket}/{key}");
+            let object = self.objects.lock().unwrap().get(&path).cloned();
+            Box::pin(async move { object.ok_or(S3ClientError::NotFound(path)) })
+        }
+
+        fn put_object<'a>(
+            &'a self,
+            bucket: &'a str,
+            key: &'a str,
+            body: Bytes,
+        ) -> BoxFuture<'a, Result<PutObjectOutput>> {
+            let mut objects = self.objects.lock().unwrap();
+            objects.insert(format!("{bucket}/{key}"), body);
+            Box::pin(async { Ok(PutObjectOutput { e_tag: None }) })
+        }
+
+        fn head_object<'a>(
+            &'a self,
+            bucket: &'a str,
+            key: &'a str,
+        ) -> BoxFuture<'a, Result<ObjectMetadata>> {
+            Box::pin(async move {
+                let body = self.get(bucket, key, None).await?;
+                Ok(ObjectMetadata {
+                    content_length: Some(body.len() as u64),
+                    e_tag: None,
+                    last_modified: None,
+                    content_type: None,
+                })
+            })
+        }
+
+        fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+            self.objects
+                .lock()
+                .unwrap()
+                .remove(&format!("{bucket}/{key}"));
+            Box::pin(async { Ok(()) })
+        }
+
+        fn list_objects_v2<'a>(
+            &'a self,
+            bucket: &'a str,
+            prefix: Option<&'a str>,
+            _continuation_token: Option<&'a str>,
+        ) -> BoxFuture<'a, Result<ListObjectsV2Output>> {
+            let prefix = format!("{bucket}/{}", prefix.unwrap_or_default());
+            let contents = self
+                .objects
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(path, _)| path.starts_with(&prefix))
+                .map(|(path, body)| ObjectInfo {
+                    key: path[bucket.len() + 1..].to_string(),
+                    size: body.len() as u64,
+                    e_tag: None,
+                    last_modified: Utc::now(),
+                })
+                .collect();
+            Box::pin(async {
+                Ok(ListObjectsV2Output {
+                    contents,
+                    is_truncated: false,
+                    next_continuation_token: None,
+                })
+            })
+        }
+    }
+
+    /// Moves every object under `prefix` to `dest`, as code under test might
+    async fn move_prefix(
+        store: &dyn ObjectStore,
+        bucket: &str,
+        prefix: &str,
+        dest: &str,
+    ) -> Result<usize> {
+        let listing = store.list_objects_v2(bucket, Some(prefix), None).await?;
+        for object in &listing.contents {
+            let body = store.get(bucket, &object.key, None).await?;
+            let key = format!("{dest}{}", &object.key[prefix.len()..]);
+            store.put_object(bucket, &key, body).await?;
+            store.delete_object(bucket, &object.key).await?;
+        }
+        Ok(listing.contents.len())
+    }
+
+    #[tokio::test]
+    async fn test_fake_object_store() {
+        let store: Box<dyn ObjectStore> = Box::<FakeStore>::default();
+        store
+            .put_object("bucket", "tmp/a", Bytes::from_static(b"a"))
+            .await
+            .unwrap();
+        store
+            .put_object("bucket", "tmp/b", Bytes::from_static(b"bb"))
+            .await
+            .unwrap();
+        store
+            .put_object("bucket", "other", Bytes::from_static(b"c"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            move_prefix(store.as_ref(), "bucket", "tmp/", "done/")
+                .await
+                .unwrap(),
+            2
+        );
+
+        let listing = store.list_objects_v2("bucket", None, None).await.unwrap();
+        let keys = listing
+            .contents
+            .iter()
+            .map(|o| o.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["done/a", "done/b", "other"]);
+        let metadata = store.head_object("bucket", "done/b").await.unwrap();
+        assert_eq!(metadata.content_length, Some(2));
+        assert!(matches!(
+            store.get("bucket", "tmp/a", None).await,
+            Err(S3ClientError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,577 @@
+//! An in-memory S3 server for integration tests, enabled by the `testing` feature
+//!
+//! [`MockS3`] verifies the SigV4 signature of every request, stores objects sent by
+//! `PutObject` and serves them, including ranges, to `GetObject`, `HeadObject` and
+//! `DeleteObject`, and records each request, including its canonical request, for
+//! assertions. Responses for other operations, or errors, are queued with
+//! [`MockS3::respond`]
+//!
+//! Buckets are addressed in the path, so clients must use
+//! [`S3ClientBuilder::force_path_style`](crate::client::S3ClientBuilder::force_path_style),
+//! and presigned URLs are not supported
+//!
+//! ```no_run
+//! # async fn example() -> s3_client::error::Result<()> {
+//! # use std::sync::Arc;
+//! # use s3_client::client::S3ClientBuilder;
+//! # use s3_client::credentials::{AwsCredential, StaticCredentialProvider};
+//! # use s3_client::testing::MockS3;
+//! let credential = Arc::new(AwsCredential {
+//!     key_id: "AKIDEXAMPLE".into(),
+//!     secret_key: "secret".into(),
+//!     token: None,
+//!     expiry: None,
+//! });
+//! let server = MockS3::start(credential.clone(), "us-east-1").await?;
+//! let client = S3ClientBuilder::default()
+//!     .endpoint(server.endpoint())
+//!     .region("us-east-1")
+//!     .force_path_style(true)
+//!     .credentials(Arc::new(StaticCredentialProvider { credential }))
+//!     .build_tokio()?;
+//!
+//! client.put_object("bucket", "key", "hello".into()).await?;
+//! assert!(server.requests()[0].canonical_request.is_some());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::credentials::{
+    canonical_request, payload_sha256, AwsCredential, RequestSigner, STREAMING_PAYLOAD,
+    UNSIGNED_PAYLOAD,
+};
+use crate::error::Result;
+use bytes::{Buf, Bytes};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use hyper::header::{
+    HeaderName, HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE,
+    IF_MATCH, RANGE,
+};
+use hyper::http::request::Parts;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, HeaderMap, Method, Request, Response, Server, StatusCode, Uri};
+use md5::{Digest, Md5};
+use percent_encoding::percent_decode_str;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// A request received by [`MockS3`]
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub method: Method,
+    /// The path and query of the request
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    /// The body as sent, i.e. still `aws-chunked` encoded for streaming uploads
+    pub body: Bytes,
+    /// The canonical request the server computed to verify the signature, `None` if
+    /// the request was not signed
+    pub canonical_request: Option<String>,
+    /// Whether the signature of the request was valid
+    pub signature_valid: bool,
+}
+
+/// A canned response of [`MockS3`], see [`MockS3::respond`]
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl MockResponse {
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// Returns a response with an S3 error document
+    pub fn error(status: StatusCode, code: &str, message: &str) -> Self {
+        Self::new(status).body(format!(
+            "<Error><Code>{code}</Code><Message>{message}</Message></Error>"
+        ))
+    }
+
+    /// Adds a header to the response
+    ///
+    /// # Panics
+    ///
+    /// If `name` or `value` are not valid in a header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.append(
+            HeaderName::from_bytes(name.as_bytes()).expect("valid header name"),
+            HeaderValue::from_str(value).expect("valid header value"),
+        );
+        self
+    }
+
+    /// Sets the body of the response
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    fn into_response(self) -> Response<Body> {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        response
+    }
+}
+
+#[derive(Debug)]
+struct StoredObject {
+    body: Bytes,
+    content_type: Option<HeaderValue>,
+    e_tag: String,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    objects: HashMap<String, StoredObject>,
+    responses: VecDeque<(Method, String, MockResponse)>,
+    requests: Vec<ReceivedRequest>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// `None` if requests are not signed
+    credential: Option<Arc<AwsCredential>>,
+    region: String,
+    state: Mutex<State>,
+}
+
+/// An in-memory S3 server listening on localhost, which stops when dropped
+#[derive(Debug)]
+pub struct MockS3 {
+    addr: SocketAddr,
+    inner: Arc<Inner>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockS3 {
+    /// Starts a server that only accepts requests signed with `credential` for `region`
+    pub async fn start(credential: Arc<AwsCredential>, region: &str) -> Result<Self> {
+        Self::start_with(Some(credential), region)
+    }
+
+    /// Starts a server that only accepts unsigned requests, as sent by an
+    /// [`anonymous`](crate::client::S3ClientBuilder::anonymous) client
+    pub async fn start_anonymous() -> Result<Self> {
+        Self::start_with(None, "us-east-1")
+    }
+
+    fn start_with(credential: Option<Arc<AwsCredential>>, region: &str) -> Result<Self> {
+        let inner = Arc::new(Inner {
+            credential,
+            region: region.to_string(),
+            state: Default::default(),
+        });
+
+        let service_inner = inner.clone();
+        let make_service = make_service_fn(move |_| {
+            let inner = service_inner.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let inner = inner.clone();
+                    async move { Ok::<_, Infallible>(inner.handle(request).await) }
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&SocketAddr::from(([127, 0, 0, 1], 0)))?.serve(make_service);
+        let addr = server.local_addr();
+        let (shutdown, stopped) = oneshot::channel();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = stopped.await;
+        }));
+
+        Ok(Self {
+            addr,
+            inner,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Returns the endpoint of the server, e.g. `http://127.0.0.1:1234`
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Queues `response` for the next `method` request to `path`, e.g. `/bucket/key`,
+    /// ignoring the query. Queued responses take precedence over stored objects and
+    /// are returned once each, in order
+    pub fn respond(&self, method: Method, path: &str, response: MockResponse) {
+        let mut state = self.inner.state.lock().unwrap();
+        state
+            .responses
+            .push_back((method, path.to_string(), response));
+    }
+
+    /// Stores an object as if it had been uploaded
+    pub fn insert_object(&self, bucket: &str, key: &str, body: impl Into<Bytes>) {
+        let body = body.into();
+        let mut state = self.inner.state.lock().unwrap();
+        state.objects.insert(
+            format!("{bucket}/{key}"),
+            StoredObject {
+                e_tag: e_tag(&body),
+                body,
+                content_type: None,
+            },
+        );
+    }
+
+    /// Returns the body of a stored object
+    pub fn object(&self, bucket: &str, key: &str) -> Option<Bytes> {
+        let state = self.inner.state.lock().unwrap();
+        state
+            .objects
+            .get(&format!("{bucket}/{key}"))
+            .map(|object| object.body.clone())
+    }
+
+    /// Returns the requests received so far, in order
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.inner.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockS3 {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+impl Inner {
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let (parts, body) = request.into_parts();
+        let body = match hyper::body::to_bytes(body).await {
+            Ok(body) => body,
+            Err(e) => return error(StatusCode::BAD_REQUEST, "IncompleteBody", &e.to_string()),
+        };
+
+        let (canonical_request, verified) = self.verify(&parts, &body);
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(ReceivedRequest {
+            method: parts.method.clone(),
+            uri: parts.uri.clone(),
+            headers: parts.headers.clone(),
+            body: body.clone(),
+            signature_valid: verified.is_ok(),
+            canonical_request,
+        });
+
+        if let Err((code, message)) = verified {
+            return error(StatusCode::FORBIDDEN, code, message);
+        }
+
+        let path = parts.uri.path();
+        let canned = state
+            .responses
+            .iter()
+            .position(|(method, p, _)| *method == parts.method && p == path);
+        if let Some(idx) = canned {
+            let (_, _, response) = state.responses.remove(idx).unwrap();
+            return response.into_response();
+        }
+
+        let id = percent_decode_str(path.trim_start_matches('/')).decode_utf8_lossy();
+        if parts.uri.query().is_some() || !id.contains('/') {
+            return error(
+                StatusCode::NOT_IMPLEMENTED,
+                "NotImplemented",
+                "only object requests without a query are supported",
+            );
+        }
+
+        match parts.method {
+            Method::GET | Method::HEAD => match state.objects.get(id.as_ref()) {
+                Some(object) => {
+                    let header = |name| parts.headers.get(name).and_then(|v| v.to_str().ok());
+                    if header(IF_MATCH).is_some_and(|e_tag| e_tag != object.e_tag) {
+                        return error(
+                            StatusCode::PRECONDITION_FAILED,
+                            "PreconditionFailed",
+                            "the entity tag does not match",
+                        );
+                    }
+
+                    let len = object.body.len();
+                    let mut response = Response::builder();
+                    let body = match header(RANGE).map(|range| parse_range(range, len)) {
+                        Some(Some(range)) => {
+                            response = response.status(StatusCode::PARTIAL_CONTENT).header(
+                                CONTENT_RANGE,
+                                format!("bytes {}-{}/{len}", range.start, range.end - 1),
+                            );
+                            object.body.slice(range)
+                        }
+                        Some(None) => {
+                            return error(
+                                StatusCode::RANGE_NOT_SATISFIABLE,
+                                "InvalidRange",
+                                "the range is not satisfiable",
+                            )
+                        }
+                        None => object.body.clone(),
+                    };
+
+                    response = response
+                        .header(CONTENT_LENGTH, body.len())
+                        .header("etag", &object.e_tag);
+                    if let Some(content_type) = &object.content_type {
+                        response = response.header(CONTENT_TYPE, content_type);
+                    }
+                    let body = match parts.method {
+                        Method::GET => Body::from(body),
+                        _ => Body::empty(),
+                    };
+                    response.body(body).unwrap()
+                }
+                None => error(StatusCode::NOT_FOUND, "NoSuchKey", "the key does not exist"),
+            },
+            Method::PUT => {
+                let body = match is_chunked(&parts.headers) {
+                    true => match decode_chunked(body) {
+                        Some(body) => body,
+                        None => {
+                            return error(
+                                StatusCode::BAD_REQUEST,
+                                "IncompleteBody",
+                                "invalid aws-chunked body",
+                            )
+                        }
+                    },
+                    false => body,
+                };
+                let e_tag = e_tag(&body);
+                state.objects.insert(
+                    id.into_owned(),
+                    StoredObject {
+                        body,
+                        content_type: parts.headers.get(CONTENT_TYPE).cloned(),
+                        e_tag: e_tag.clone(),
+                    },
+                );
+                Response::builder()
+                    .header("etag", e_tag)
+                    .body(Body::empty())
+                    .unwrap()
+            }
+            Method::DELETE => {
+                state.objects.remove(id.as_ref());
+                Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty())
+                    .unwrap()
+            }
+            _ => error(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "MethodNotAllowed",
+                "the method is not supported",
+            ),
+        }
+    }
+
+    /// Verifies the signature and payload hash of a request, returning the canonical
+    /// request, if signed, and the error code and message if verification failed
+    #[allow(clippy::type_complexity)]
+    fn verify(
+        &self,
+        parts: &Parts,
+        body: &[u8],
+    ) -> (
+        Option<String>,
+        std::result::Result<(), (&'static str, &'static str)>,
+    ) {
+        let authorization = parts
+            .headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+
+        let (credential, authorization) = match (&self.credential, authorization) {
+            (None, None) => return (None, Ok(())),
+            (None, Some(_)) => {
+                return (None, Err(("AccessDenied", "expected an unsigned request")))
+            }
+            (Some(_), None) => return (None, Err(("AccessDenied", "missing authorization"))),
+            (Some(credential), Some(authorization)) => (credential, authorization),
+        };
+
+        let invalid = (
+            "AuthorizationHeaderMalformed",
+            "invalid authorization header",
+        );
+        let Some(auth) = parse_authorization(authorization) else {
+            return (None, Err(invalid));
+        };
+        let scope = auth.credential.split('/').collect::<Vec<_>>();
+        let [key_id, _, region, service, "aws4_request"] = scope[..] else {
+            return (None, Err(invalid));
+        };
+        if key_id != credential.key_id {
+            return (None, Err(("InvalidAccessKeyId", "unknown access key id")));
+        }
+        if region != self.region {
+            return (None, Err(invalid));
+        }
+
+        let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+        let Some(date) = header("x-amz-date")
+            .and_then(|date| NaiveDateTime::parse_from_str(date, "%Y%m%dT%H%M%SZ").ok())
+        else {
+            return (None, Err(("AccessDenied", "missing or invalid x-amz-date")));
+        };
+        let Some(payload) = header("x-amz-content-sha256") else {
+            return (
+                None,
+                Err(("InvalidRequest", "missing x-amz-content-sha256")),
+            );
+        };
+
+        let mut signed = HeaderMap::new();
+        for name in auth.signed_headers.split(';') {
+            for value in parts.headers.get_all(name) {
+                signed.append(
+                    HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                    value.clone(),
+                );
+            }
+        }
+
+        let Ok((signed_headers, canonical)) =
+            canonical_request(&parts.method, &parts.uri, &signed, payload)
+        else {
+            return (None, Err(invalid));
+        };
+
+        let signer = RequestSigner {
+            date: Utc.from_utc_datetime(&date),
+            credential,
+            service,
+            region,
+        };
+        let mismatch = ("SignatureDoesNotMatch", "the signature does not match");
+        if signed_headers != auth.signed_headers || signer.signature(&canonical) != auth.signature {
+            return (Some(canonical), Err(mismatch));
+        }
+
+        if ![UNSIGNED_PAYLOAD, STREAMING_PAYLOAD].contains(&payload)
+            && payload_sha256(body) != payload
+        {
+            let mismatch = (
+                "XAmzContentSHA256Mismatch",
+                "the payload hash does not match",
+            );
+            return (Some(canonical), Err(mismatch));
+        }
+
+        (Some(canonical), Ok(()))
+    }
+}
+
+/// Parses a `Range` header of the form `bytes=start-end`, `bytes=start-` or
+/// `bytes=-suffix` into the byte range it selects of an object of `len` bytes,
+/// returning `None` if it is malformed or not satisfiable
+fn parse_range(range: &str, len: usize) -> Option<std::ops::Range<usize>> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let range = match (start, end) {
+        ("", suffix) => len.saturating_sub(suffix.parse().ok()?)..len,
+        (start, "") => start.parse().ok()?..len,
+        (start, end) => {
+            // An end past the object, even the last `usize`, selects up to its end
+            let end = end.parse::<usize>().ok()?.checked_add(1);
+            start.parse().ok()?..end.map_or(len, |end| len.min(end))
+        }
+    };
+    match range.start < range.end {
+        true => Some(range),
+        false => None,
+    }
+}
+
+struct Authorization<'a> {
+    credential: &'a str,
+    signed_headers: &'a str,
+    signature: &'a str,
+}
+
+/// Parses `AWS4-HMAC-SHA256 Credential=..., SignedHeaders=..., Signature=...`
+fn parse_authorization(value: &str) -> Option<Authorization<'_>> {
+    let mut fields = HashMap::new();
+    for field in value.strip_prefix("AWS4-HMAC-SHA256 ")?.split(',') {
+        let (name, value) = field.trim().split_once('=')?;
+        fields.insert(name, value);
+    }
+
+    Some(Authorization {
+        credential: fields.get("Credential")?,
+        signed_headers: fields.get("SignedHeaders")?,
+        signature: fields.get("Signature")?,
+    })
+}
+
+fn is_chunked(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_ENCODING)
+        .is_some_and(|v| v.as_bytes() == b"aws-chunked")
+}
+
+/// Decodes an `aws-chunked` body, without verifying the chunk signatures
+fn decode_chunked(mut body: Bytes) -> Option<Bytes> {
+    let mut decoded = Vec::with_capacity(body.len());
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let line = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(line.split(';').next()?, 16).ok()?;
+        body.advance(line_end + 2);
+
+        if body.len() < size + 2 || &body[size..size + 2] != b"\r\n" {
+            return None;
+        }
+        decoded.extend_from_slice(&body[..size]);
+        body.advance(size + 2);
+
+        if size == 0 {
+            return Some(decoded.into());
+        }
+    }
+}
+
+/// Returns the quoted entity tag of an object uploaded in one part, its MD5 digest
+fn e_tag(body: &[u8]) -> String {
+    format!("\"{:x}\"", Md5::digest(body))
+}
+
+fn error(status: StatusCode, code: &str, message: &str) -> Response<Body> {
+    MockResponse::error(status, code, message).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=2-4", 10), Some(2..5));
+        assert_eq!(parse_range("bytes=7-", 10), Some(7..10));
+        assert_eq!(parse_range("bytes=-2", 10), Some(8..10));
+        assert_eq!(parse_range("bytes=8-100", 10), Some(8..10));
+        assert_eq!(
+            parse_range(&format!("bytes=8-{}", usize::MAX), 10),
+            Some(8..10)
+        );
+        assert_eq!(parse_range("bytes=10-", 10), None);
+        assert_eq!(parse_range("bytes=5-4", 10), None);
+        assert_eq!(parse_range("items=0-4", 10), None);
+    }
+}
@@ -0,0 +1,1456 @@
+use crate::checksum::{content_md5, ChecksumAlgorithm};
+use crate::client::{format_http_range, header_string};
+use crate::error::{Result, S3ClientError};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use hyper::header::{
+    CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    IF_UNMODIFIED_SINCE, LAST_MODIFIED, RANGE,
+};
+use hyper::http::request;
+use hyper::HeaderMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Range, RangeFrom};
+use std::str::FromStr;
+
+const SSE_HEADER: &str = "x-amz-server-side-encryption";
+const SSE_KMS_KEY_ID_HEADER: &str = "x-amz-server-side-encryption-aws-kms-key-id";
+const SSE_CUSTOMER_ALGORITHM_HEADER: &str = "x-amz-server-side-encryption-customer-algorithm";
+const SSE_CUSTOMER_KEY_HEADER: &str = "x-amz-server-side-encryption-customer-key";
+const SSE_CUSTOMER_KEY_MD5_HEADER: &str = "x-amz-server-side-encryption-customer-key-md5";
+
+/// A range of bytes of an object to fetch, see [`format_http_range`](crate::client::format_http_range)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// Everything from the given offset to the end of the object
+    FromStart(usize),
+    /// The bytes from the first offset up to, but excluding, the second
+    FromStartTo(usize, usize),
+    /// The last `n` bytes of the object
+    Last(usize),
+}
+
+impl ByteRange {
+    /// Returns an error if the range selects no bytes
+    pub(crate) fn validate(self) -> Result<Self> {
+        match self {
+            Self::FromStartTo(start, end) if start >= end => Err(S3ClientError::InvalidRange(
+                format!("{start}..{end} is empty or inverted"),
+            )),
+            Self::Last(0) => Err(S3ClientError::InvalidRange(
+                "suffix range of 0 bytes".to_string(),
+            )),
+            range => Ok(range),
+        }
+    }
+}
+
+impl From<Range<usize>> for ByteRange {
+    fn from(range: Range<usize>) -> Self {
+        Self::FromStartTo(range.start, range.end)
+    }
+}
+
+impl From<RangeFrom<usize>> for ByteRange {
+    fn from(range: RangeFrom<usize>) -> Self {
+        Self::FromStart(range.start)
+    }
+}
+
+/// Options of [`S3Client::get_with`](crate::client::S3Client::get_with) and
+/// [`S3Client::head_object_with`](crate::client::S3Client::head_object_with)
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/API/API_GetObject.html#API_GetObject_RequestSyntax>
+#[derive(Debug, Clone, Default)]
+pub struct GetObjectOptions {
+    /// Fetch only this range of the object
+    pub range: Option<ByteRange>,
+    /// Fail with [`S3ClientError::PreconditionFailed`] unless the entity tag matches
+    pub if_match: Option<String>,
+    /// Return [`Conditional::NotModified`] if the entity tag matches
+    pub if_none_match: Option<String>,
+    /// Return [`Conditional::NotModified`] unless modified after this time
+    pub if_modified_since: Option<DateTime<Utc>>,
+    /// Fail with [`S3ClientError::PreconditionFailed`] if modified after this time
+    pub if_unmodified_since: Option<DateTime<Utc>>,
+    /// The key the object was encrypted with, required to read objects uploaded with
+    /// [`ServerSideEncryption::CustomerKey`]
+    pub sse_customer_key: Option<CustomerKey>,
+}
+
+impl GetObjectOptions {
+    /// Adds the headers for these options to `request`
+    pub(crate) fn apply(&self, mut request: request::Builder) -> Result<request::Builder> {
+        if let Some(range) = self.range {
+            request = request.header(RANGE, format_http_range(range.validate()?));
+        }
+        if let Some(etag) = &self.if_match {
+            request = request.header(IF_MATCH, etag);
+        }
+        if let Some(etag) = &self.if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(date) = self.if_modified_since {
+            request = request.header(IF_MODIFIED_SINCE, format_http_date(date));
+        }
+        if let Some(date) = self.if_unmodified_since {
+            request = request.header(IF_UNMODIFIED_SINCE, format_http_date(date));
+        }
+        if let Some(key) = &self.sse_customer_key {
+            request = key.apply(request);
+        }
+        Ok(request)
+    }
+}
+
+impl From<Option<ByteRange>> for GetObjectOptions {
+    fn from(range: Option<ByteRange>) -> Self {
+        Self {
+            range,
+            ..Default::default()
+        }
+    }
+}
+
+/// The result of a conditional request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conditional<T> {
+    /// The object matched the conditions
+    Modified(T),
+    /// The object has not been modified, as determined by
+    /// [`GetObjectOptions::if_none_match`] or [`GetObjectOptions::if_modified_since`]
+    NotModified,
+}
+
+/// Formats `date` as an HTTP date, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`
+fn format_http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// An upload of an object with [`S3Client::put_object_with`](crate::client::S3Client::put_object_with)
+#[derive(Debug, Clone)]
+pub struct PutObjectRequest {
+    pub(crate) bucket: String,
+    pub(crate) key: String,
+    pub(crate) body: Bytes,
+    pub(crate) content_type: Option<String>,
+    pub(crate) metadata: Vec<(String, String)>,
+    pub(crate) encryption: Option<ServerSideEncryption>,
+    pub(crate) checksum_algorithm: Option<ChecksumAlgorithm>,
+    pub(crate) storage_class: Option<StorageClass>,
+    pub(crate) object_lock: Option<(ObjectLockMode, DateTime<Utc>)>,
+    pub(crate) legal_hold: Option<bool>,
+    pub(crate) acl: Option<CannedAcl>,
+    pub(crate) if_match: Option<String>,
+    pub(crate) if_none_match: Option<String>,
+}
+
+impl PutObjectRequest {
+    pub fn new(bucket: impl Into<String>, key: impl Into<String>, body: Bytes) -> Self {
+        Self {
+            bucket: bucket.into(),
+            key: key.into(),
+            body,
+            content_type: None,
+            metadata: Vec::new(),
+            encryption: None,
+            checksum_algorithm: None,
+            storage_class: None,
+            object_lock: None,
+            legal_hold: None,
+            acl: None,
+            if_match: None,
+            if_none_match: None,
+        }
+    }
+
+    /// Sets the `Content-Type` returned when the object is fetched
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Adds user metadata, stored with the object as the header `x-amz-meta-{key}`
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Encrypts the object at rest with `encryption`
+    pub fn server_side_encryption(mut self, encryption: ServerSideEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Sends a checksum of the body computed with `algorithm`, which S3 verifies
+    /// and stores with the object
+    pub fn checksum_algorithm(mut self, algorithm: ChecksumAlgorithm) -> Self {
+        self.checksum_algorithm = Some(algorithm);
+        self
+    }
+
+    /// Stores the object in `storage_class` rather than `STANDARD`
+    pub fn storage_class(mut self, storage_class: StorageClass) -> Self {
+        self.storage_class = Some(storage_class);
+        self
+    }
+
+    /// Protects the object from being deleted or overwritten in `mode` until
+    /// `retain_until`. The bucket must have Object Lock enabled
+    pub fn object_lock(mut self, mode: ObjectLockMode, retain_until: DateTime<Utc>) -> Self {
+        self.object_lock = Some((mode, retain_until));
+        self
+    }
+
+    /// Places a legal hold on the object, protecting it until the hold is removed
+    /// regardless of any retention period
+    pub fn legal_hold(mut self, legal_hold: bool) -> Self {
+        self.legal_hold = Some(legal_hold);
+        self
+    }
+
+    /// Grants access to the object with a canned ACL rather than the default `private`
+    pub fn acl(mut self, acl: CannedAcl) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Only overwrites the object if its entity tag is `e_tag`, i.e. it hasn't been
+    /// modified since it was read, failing with [`S3ClientError::PreconditionFailed`]
+    /// otherwise
+    pub fn if_match(mut self, e_tag: impl Into<String>) -> Self {
+        self.if_match = Some(e_tag.into());
+        self
+    }
+
+    /// Only creates the object if it doesn't exist yet, failing with
+    /// [`S3ClientError::PreconditionFailed`] otherwise
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_none_match = Some("*".to_string());
+        self
+    }
+}
+
+/// A copy of an object with [`S3Client::copy_object_with`](crate::client::S3Client::copy_object_with)
+#[derive(Debug, Clone)]
+pub struct CopyObjectRequest {
+    pub(crate) src_bucket: String,
+    pub(crate) src_key: String,
+    pub(crate) dst_bucket: String,
+    pub(crate) dst_key: String,
+    pub(crate) metadata_directive: Option<MetadataDirective>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) metadata: Vec<(String, String)>,
+}
+
+impl CopyObjectRequest {
+    pub fn new(
+        src_bucket: impl Into<String>,
+        src_key: impl Into<String>,
+        dst_bucket: impl Into<String>,
+        dst_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            src_bucket: src_bucket.into(),
+            src_key: src_key.into(),
+            dst_bucket: dst_bucket.into(),
+            dst_key: dst_key.into(),
+            metadata_directive: None,
+            content_type: None,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Sets whether the copy keeps the metadata of the source, the default, or
+    /// replaces it with the content type and metadata of this request
+    ///
+    /// Replacing the metadata of an object copied onto itself is the way to change
+    /// the metadata of an existing object
+    pub fn metadata_directive(mut self, directive: MetadataDirective) -> Self {
+        self.metadata_directive = Some(directive);
+        self
+    }
+
+    /// Sets the `Content-Type` of the copy, requires [`MetadataDirective::Replace`]
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Adds user metadata to the copy, stored as the header `x-amz-meta-{key}`,
+    /// requires [`MetadataDirective::Replace`]
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Whether a copy keeps the metadata of its source, sent as `x-amz-metadata-directive`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataDirective {
+    /// The copy has the content type and user metadata of the source
+    Copy,
+    /// The copy has the content type and user metadata given in the request
+    Replace,
+}
+
+impl MetadataDirective {
+    /// Returns the name of the directive, as sent in `x-amz-metadata-directive`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Copy => "COPY",
+            Self::Replace => "REPLACE",
+        }
+    }
+}
+
+/// How an object's retention period is enforced by S3 Object Lock
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/object-lock.html>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectLockMode {
+    /// Users with the `s3:BypassGovernanceRetention` permission may still delete
+    /// the object or shorten its retention
+    Governance,
+    /// No user, including the root user, may delete the object until it expires
+    Compliance,
+}
+
+impl ObjectLockMode {
+    /// Returns the name of the mode, as sent in `x-amz-object-lock-mode`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Governance => "GOVERNANCE",
+            Self::Compliance => "COMPLIANCE",
+        }
+    }
+}
+
+impl FromStr for ObjectLockMode {
+    type Err = S3ClientError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "GOVERNANCE" => Ok(Self::Governance),
+            "COMPLIANCE" => Ok(Self::Compliance),
+            _ => Err(S3ClientError::InvalidArgument(format!(
+                "unknown object lock mode {s}"
+            ))),
+        }
+    }
+}
+
+// Serialized by name, as quick-xml would otherwise write unit variants as elements
+impl Serialize for ObjectLockMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectLockMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let mode = String::deserialize(deserializer)?;
+        mode.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The versioning state of a bucket
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/Versioning.html>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersioningStatus {
+    /// Every write of an object creates a new version
+    Enabled,
+    /// Versioning was enabled, but new writes replace the `null` version. Existing
+    /// versions are kept
+    Suspended,
+    /// Versioning was never enabled. Once enabled, a bucket can only be suspended
+    Disabled,
+}
+
+impl VersioningStatus {
+    /// Returns the name of the state, as sent in `VersioningConfiguration`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Enabled => "Enabled",
+            Self::Suspended => "Suspended",
+            Self::Disabled => "Disabled",
+        }
+    }
+}
+
+/// A predefined access control list, parsed from its name with [`str::parse`],
+/// e.g. `"public-read".parse()`
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/acl-overview.html#canned-acl>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CannedAcl {
+    Private,
+    PublicRead,
+    PublicReadWrite,
+    AwsExecRead,
+    AuthenticatedRead,
+    BucketOwnerRead,
+    BucketOwnerFullControl,
+}
+
+impl CannedAcl {
+    const ALL: [Self; 7] = [
+        Self::Private,
+        Self::PublicRead,
+        Self::PublicReadWrite,
+        Self::AwsExecRead,
+        Self::AuthenticatedRead,
+        Self::BucketOwnerRead,
+        Self::BucketOwnerFullControl,
+    ];
+
+    /// Returns the name of the ACL, as sent in `x-amz-acl`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::PublicRead => "public-read",
+            Self::PublicReadWrite => "public-read-write",
+            Self::AwsExecRead => "aws-exec-read",
+            Self::AuthenticatedRead => "authenticated-read",
+            Self::BucketOwnerRead => "bucket-owner-read",
+            Self::BucketOwnerFullControl => "bucket-owner-full-control",
+        }
+    }
+}
+
+impl FromStr for CannedAcl {
+    type Err = S3ClientError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|acl| acl.as_str() == s)
+            .ok_or_else(|| S3ClientError::InvalidArgument(format!("unknown canned ACL {s}")))
+    }
+}
+
+/// The retention period of an object protected by S3 Object Lock, the body of
+/// `GetObjectRetention` and `PutObjectRetention`
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ObjectLockRetention.html>
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Retention {
+    pub mode: ObjectLockMode,
+    /// Until when the object is protected
+    pub retain_until_date: DateTime<Utc>,
+}
+
+/// The storage class of an object, which determines its availability and cost, parsed
+/// from its name with [`str::parse`], e.g. `"STANDARD_IA".parse()`
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-class-intro.html>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    Standard,
+    ReducedRedundancy,
+    StandardIa,
+    OnezoneIa,
+    IntelligentTiering,
+    Glacier,
+    GlacierIr,
+    DeepArchive,
+    Outposts,
+    Snow,
+    ExpressOnezone,
+}
+
+impl StorageClass {
+    const ALL: [Self; 11] = [
+        Self::Standard,
+        Self::ReducedRedundancy,
+        Self::StandardIa,
+        Self::OnezoneIa,
+        Self::IntelligentTiering,
+        Self::Glacier,
+        Self::GlacierIr,
+        Self::DeepArchive,
+        Self::Outposts,
+        Self::Snow,
+        Self::ExpressOnezone,
+    ];
+
+    /// Returns the name of the storage class, as sent in `x-amz-storage-class`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "STANDARD",
+            Self::ReducedRedundancy => "REDUCED_REDUNDANCY",
+            Self::StandardIa => "STANDARD_IA",
+            Self::OnezoneIa => "ONEZONE_IA",
+            Self::IntelligentTiering => "INTELLIGENT_TIERING",
+            Self::Glacier => "GLACIER",
+            Self::GlacierIr => "GLACIER_IR",
+            Self::DeepArchive => "DEEP_ARCHIVE",
+            Self::Outposts => "OUTPOSTS",
+            Self::Snow => "SNOW",
+            Self::ExpressOnezone => "EXPRESS_ONEZONE",
+        }
+    }
+}
+
+impl FromStr for StorageClass {
+    type Err = S3ClientError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|class| class.as_str() == s)
+            .ok_or_else(|| S3ClientError::InvalidArgument(format!("unknown storage class {s}")))
+    }
+}
+
+/// How S3 encrypts an object at rest
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/serv-side-encryption.html>
+#[derive(Clone, PartialEq, Eq)]
+pub enum ServerSideEncryption {
+    /// Keys managed by S3 (SSE-S3)
+    Aes256,
+    /// Keys managed by KMS (SSE-KMS), using the given key id or ARN, or the default
+    /// `aws/s3` key if `None`
+    Kms(Option<String>),
+    /// A 256 bit key provided by the caller (SSE-C), which must then also be provided
+    /// to read the object with [`GetObjectOptions::sse_customer_key`]. S3 only
+    /// accepts SSE-C requests over `https`
+    CustomerKey([u8; 32]),
+}
+
+impl ServerSideEncryption {
+    /// Adds the headers requesting this encryption to `request`
+    pub(crate) fn apply(&self, request: request::Builder) -> request::Builder {
+        match self {
+            Self::Aes256 => request.header(SSE_HEADER, "AES256"),
+            Self::Kms(key_id) => {
+                let request = request.header(SSE_HEADER, "aws:kms");
+                match key_id {
+                    Some(key_id) => request.header(SSE_KMS_KEY_ID_HEADER, key_id),
+                    None => request,
+                }
+            }
+            Self::CustomerKey(key) => CustomerKey(*key).apply(request),
+        }
+    }
+}
+
+impl std::fmt::Debug for ServerSideEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Aes256 => write!(f, "Aes256"),
+            Self::Kms(key_id) => f.debug_tuple("Kms").field(key_id).finish(),
+            // Don't leak the key into logs
+            Self::CustomerKey(_) => write!(f, "CustomerKey(..)"),
+        }
+    }
+}
+
+/// A 256 bit key provided by the caller to read an object encrypted with SSE-C, see
+/// [`ServerSideEncryption::CustomerKey`]
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/ServerSideEncryptionCustomerKeys.html>
+#[derive(Clone, PartialEq, Eq)]
+pub struct CustomerKey(pub [u8; 32]);
+
+impl CustomerKey {
+    /// Adds the algorithm, base64 encoded key and key MD5 headers to `request`
+    pub(crate) fn apply(&self, request: request::Builder) -> request::Builder {
+        request
+            .header(SSE_CUSTOMER_ALGORITHM_HEADER, "AES256")
+            .header(SSE_CUSTOMER_KEY_HEADER, BASE64_STANDARD.encode(self.0))
+            .header(SSE_CUSTOMER_KEY_MD5_HEADER, content_md5(&self.0))
+    }
+}
+
+impl std::fmt::Debug for CustomerKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the key into logs
+        write!(f, "CustomerKey(..)")
+    }
+}
+
+/// The result of a successful [`S3Client::put_object`](crate::client::S3Client::put_object)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PutObjectOutput {
+    /// The entity tag of the uploaded object
+    pub e_tag: Option<String>,
+}
+
+/// The result of a successful [`S3Client::copy_object`](crate::client::S3Client::copy_object)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CopyObjectOutput {
+    /// The entity tag of the new object
+    #[serde(rename = "ETag")]
+    pub e_tag: Option<String>,
+    /// The time the new object was created
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Metadata of an object as returned by [`S3Client::head_object`](crate::client::S3Client::head_object)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectMetadata {
+    /// The size of the object in bytes
+    pub content_length: Option<u64>,
+    /// The entity tag of the object
+    pub e_tag: Option<String>,
+    /// The time the object was last modified
+    pub last_modified: Option<DateTime<Utc>>,
+    /// The MIME type of the object
+    pub content_type: Option<String>,
+}
+
+impl ObjectMetadata {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            content_length: header_string(headers, CONTENT_LENGTH).and_then(|v| v.parse().ok()),
+            e_tag: header_string(headers, ETAG),
+            last_modified: header_string(headers, LAST_MODIFIED)
+                .and_then(|v| DateTime::parse_from_rfc2822(&v).ok())
+                .map(|v| v.with_timezone(&Utc)),
+            content_type: header_string(headers, CONTENT_TYPE),
+        }
+    }
+}
+
+/// An object returned by [`S3Client::get_object`](crate::client::S3Client::get_object)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetObjectOutput {
+    /// The object's content, or the requested range of it
+    pub body: Bytes,
+    /// The metadata of the object, where `content_length` is the length of `body`
+    pub metadata: ObjectMetadata,
+}
+
+/// A page of results from [`S3Client::list_objects_v2`](crate::client::S3Client::list_objects_v2)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListObjectsV2Output {
+    /// The objects in this page
+    #[serde(default)]
+    pub contents: Vec<ObjectInfo>,
+    /// Whether there are more results after this page
+    #[serde(default)]
+    pub is_truncated: bool,
+    /// The token to pass to retrieve the next page, if `is_truncated`
+    pub next_continuation_token: Option<String>,
+}
+
+/// An object returned in a listing
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectInfo {
+    /// The key of the object
+    pub key: String,
+    /// The size of the object in bytes
+    pub size: u64,
+    /// The entity tag of the object
+    #[serde(rename = "ETag")]
+    pub e_tag: Option<String>,
+    /// The time the object was last modified
+    pub last_modified: DateTime<Utc>,
+}
+
+/// A page of results from
+/// [`S3Client::list_object_versions`](crate::client::S3Client::list_object_versions)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListVersionsOutput {
+    /// The versions of objects in this page
+    #[serde(rename = "Version", default)]
+    pub versions: Vec<ObjectVersion>,
+    /// The delete markers in this page, which are interleaved with the versions
+    /// in the listing S3 returns
+    #[serde(rename = "DeleteMarker", default)]
+    pub delete_markers: Vec<DeleteMarker>,
+    /// Whether there are more results after this page
+    #[serde(default)]
+    pub is_truncated: bool,
+    /// The key marker to pass to retrieve the next page, if `is_truncated`
+    pub next_key_marker: Option<String>,
+    /// The version id marker to pass to retrieve the next page, if `is_truncated`
+    pub next_version_id_marker: Option<String>,
+}
+
+/// A version of an object returned in a listing
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectVersion {
+    /// The key of the object
+    pub key: String,
+    /// The id of this version, `null` if it was written before versioning was enabled
+    pub version_id: String,
+    /// Whether this is the current version of the object
+    pub is_latest: bool,
+    /// The size of this version in bytes
+    pub size: u64,
+    /// The entity tag of this version
+    #[serde(rename = "ETag")]
+    pub e_tag: Option<String>,
+    /// The time this version was written
+    pub last_modified: DateTime<Utc>,
+}
+
+/// A delete marker returned in a listing, a placeholder version recording that an
+/// object was deleted from a versioned bucket
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteMarker {
+    /// The key of the deleted object
+    pub key: String,
+    /// The id of the delete marker
+    pub version_id: String,
+    /// Whether the delete marker is the current version of the object, i.e. the
+    /// object appears deleted
+    pub is_latest: bool,
+    /// The time the object was deleted
+    pub last_modified: DateTime<Utc>,
+}
+
+/// An attribute of an object requested from
+/// [`S3Client::get_object_attributes`](crate::client::S3Client::get_object_attributes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectAttribute {
+    ETag,
+    Checksum,
+    ObjectParts,
+    StorageClass,
+    ObjectSize,
+}
+
+impl ObjectAttribute {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::ETag => "ETag",
+            Self::Checksum => "Checksum",
+            Self::ObjectParts => "ObjectParts",
+            Self::StorageClass => "StorageClass",
+            Self::ObjectSize => "ObjectSize",
+        }
+    }
+}
+
+/// The result of [`S3Client::get_object_attributes`](crate::client::S3Client::get_object_attributes),
+/// where only the requested attributes are set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectAttributesOutput {
+    /// The entity tag of the object
+    #[serde(rename = "ETag")]
+    pub e_tag: Option<String>,
+    /// The checksum of the object, if it was uploaded with one
+    pub checksum: Option<ObjectChecksum>,
+    /// The parts of an object uploaded in parts
+    pub object_parts: Option<ObjectParts>,
+    /// The storage class of the object, e.g. `STANDARD`
+    pub storage_class: Option<String>,
+    /// The size of the object in bytes
+    pub object_size: Option<u64>,
+}
+
+/// The base64 encoded checksum of an object or part, of the algorithm it was uploaded with
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ObjectChecksum {
+    #[serde(rename = "ChecksumCRC32")]
+    pub crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    pub crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    pub sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub sha256: Option<String>,
+}
+
+/// A page of the parts of an object uploaded in parts
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectParts {
+    /// The number of parts of the object
+    pub total_parts_count: Option<u32>,
+    /// Whether there are more parts after this page
+    #[serde(default)]
+    pub is_truncated: bool,
+    /// The part number after which the next page starts, if `is_truncated`
+    pub next_part_number_marker: Option<u32>,
+    /// The parts in this page, only returned for objects uploaded with a checksum
+    #[serde(default, rename = "Part")]
+    pub parts: Vec<ObjectPart>,
+}
+
+/// A part of an object uploaded in parts, with its checksum as in [`ObjectChecksum`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectPart {
+    pub part_number: u32,
+    /// The size of the part in bytes
+    pub size: u64,
+    #[serde(rename = "ChecksumCRC32")]
+    pub crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    pub crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    pub sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    pub sha256: Option<String>,
+}
+
+/// The result of [`S3Client::list_buckets`](crate::client::S3Client::list_buckets)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListBucketsOutput {
+    /// The buckets owned by the account
+    #[serde(default, deserialize_with = "deserialize_buckets")]
+    pub buckets: Vec<Bucket>,
+    /// The owner of the buckets
+    pub owner: Option<Owner>,
+}
+
+/// A bucket returned by [`S3Client::list_buckets`](crate::client::S3Client::list_buckets)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Bucket {
+    /// The name of the bucket
+    pub name: String,
+    /// The time the bucket was created
+    pub creation_date: DateTime<Utc>,
+}
+
+/// The owner of a bucket or object
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Owner {
+    /// The canonical user id of the owner
+    #[serde(rename = "ID")]
+    pub id: Option<String>,
+    /// The display name of the owner, only returned in some regions
+    pub display_name: Option<String>,
+}
+
+/// The format of an object queried by
+/// [`S3Client::select_object_content`](crate::client::S3Client::select_object_content)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Comma separated values, whose first line names the columns if `header` is true,
+    /// so that they can be referred to by name
+    Csv {
+        header: bool,
+    },
+    /// JSON, either a single document or one document per line if `lines` is true
+    Json {
+        lines: bool,
+    },
+    Parquet,
+}
+
+/// The format of the records returned by
+/// [`S3Client::select_object_content`](crate::client::S3Client::select_object_content)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Comma separated values, one record per line
+    Csv,
+    /// One JSON document per line
+    Json,
+}
+
+/// The request body of `SelectObjectContent`
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct SelectObjectContentRequest<'a> {
+    expression: &'a str,
+    expression_type: &'static str,
+    input_serialization: InputSerialization,
+    output_serialization: OutputSerialization,
+}
+
+impl<'a> SelectObjectContentRequest<'a> {
+    pub fn new(expression: &'a str, input: InputFormat, output: OutputFormat) -> Self {
+        let input_serialization = match input {
+            InputFormat::Csv { header } => InputSerialization {
+                csv: Some(CsvInput {
+                    file_header_info: match header {
+                        true => "USE",
+                        false => "NONE",
+                    },
+                }),
+                ..Default::default()
+            },
+            InputFormat::Json { lines } => InputSerialization {
+                json: Some(JsonInput {
+                    json_type: match lines {
+                        true => "LINES",
+                        false => "DOCUMENT",
+                    },
+                }),
+                ..Default::default()
+            },
+            InputFormat::Parquet => InputSerialization {
+                parquet: Some(Empty {}),
+                ..Default::default()
+            },
+        };
+        let output_serialization = match output {
+            OutputFormat::Csv => OutputSerialization {
+                csv: Some(Empty {}),
+                json: None,
+            },
+            OutputFormat::Json => OutputSerialization {
+                csv: None,
+                json: Some(JsonOutput {
+                    record_delimiter: "\n",
+                }),
+            },
+        };
+
+        Self {
+            expression,
+            expression_type: "SQL",
+            input_serialization,
+            output_serialization,
+        }
+    }
+}
+
+/// Exactly one of the fields is set, selecting the format
+#[derive(Default, Serialize)]
+struct InputSerialization {
+    #[serde(rename = "CSV", skip_serializing_if = "Option::is_none")]
+    csv: Option<CsvInput>,
+    #[serde(rename = "JSON", skip_serializing_if = "Option::is_none")]
+    json: Option<JsonInput>,
+    #[serde(rename = "Parquet", skip_serializing_if = "Option::is_none")]
+    parquet: Option<Empty>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct CsvInput {
+    file_header_info: &'static str,
+}
+
+#[derive(Serialize)]
+struct JsonInput {
+    #[serde(rename = "Type")]
+    json_type: &'static str,
+}
+
+/// Exactly one of the fields is set, selecting the format
+#[derive(Serialize)]
+struct OutputSerialization {
+    #[serde(rename = "CSV", skip_serializing_if = "Option::is_none")]
+    csv: Option<Empty>,
+    #[serde(rename = "JSON", skip_serializing_if = "Option::is_none")]
+    json: Option<JsonOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct JsonOutput {
+    record_delimiter: &'static str,
+}
+
+/// An element without content, whose presence selects a format with default options
+#[derive(Serialize)]
+struct Empty {}
+
+/// The access control list of an object, returned by
+/// [`S3Client::get_object_acl`](crate::client::S3Client::get_object_acl)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccessControlPolicy {
+    /// The owner of the object
+    pub owner: Option<Owner>,
+    /// The permissions granted on the object
+    #[serde(
+        rename = "AccessControlList",
+        default,
+        deserialize_with = "deserialize_grants"
+    )]
+    pub grants: Vec<Grant>,
+}
+
+/// A permission granted by an [`AccessControlPolicy`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Grant {
+    pub grantee: Grantee,
+    /// e.g. `FULL_CONTROL` or `READ`
+    pub permission: String,
+}
+
+/// The account or group a [`Grant`] applies to
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Grantee {
+    /// The type of grantee, `CanonicalUser`, `AmazonCustomerByEmail` or `Group`, from
+    /// the `xsi:type` attribute whose prefix quick-xml strips
+    #[serde(rename = "@type")]
+    pub grantee_type: String,
+    /// The canonical user id of a `CanonicalUser`
+    #[serde(rename = "ID")]
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+    pub email_address: Option<String>,
+    /// The URI of a `Group`, e.g. `http://acs.amazonaws.com/groups/global/AllUsers`
+    #[serde(rename = "URI")]
+    pub uri: Option<String>,
+}
+
+/// Deserializes the `<AccessControlList>` element, which wraps a list of `<Grant>` elements
+fn deserialize_grants<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Grant>, D::Error> {
+    #[derive(Deserialize)]
+    struct AccessControlList {
+        #[serde(rename = "Grant", default)]
+        grants: Vec<Grant>,
+    }
+
+    Ok(AccessControlList::deserialize(deserializer)?.grants)
+}
+
+/// Deserializes the `<Buckets>` element, which wraps a list of `<Bucket>` elements
+fn deserialize_buckets<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Bucket>, D::Error> {
+    #[derive(Deserialize)]
+    struct Buckets {
+        #[serde(rename = "Bucket", default)]
+        buckets: Vec<Bucket>,
+    }
+
+    Ok(Buckets::deserialize(deserializer)?.buckets)
+}
+
+/// A part uploaded by [`S3Client::upload_part`](crate::client::S3Client::upload_part)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompletedPart {
+    /// The number of the part within the upload, starting at 1
+    pub part_number: u32,
+    /// The entity tag returned when the part was uploaded
+    #[serde(rename = "ETag")]
+    pub e_tag: String,
+}
+
+/// The request body of `CompleteMultipartUpload`
+#[derive(Serialize)]
+pub(crate) struct CompleteMultipartUpload {
+    #[serde(rename = "Part")]
+    pub parts: Vec<CompletedPart>,
+}
+
+/// The request body of `DeleteObjects`
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Delete<'a> {
+    #[serde(rename = "Object")]
+    pub objects: Vec<ObjectIdentifier<'a>>,
+    pub quiet: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ObjectIdentifier<'a> {
+    pub key: &'a str,
+}
+
+/// The result of [`S3Client::delete_objects`](crate::client::S3Client::delete_objects)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteObjectsOutput {
+    /// The objects that were deleted
+    #[serde(default)]
+    pub deleted: Vec<DeletedObject>,
+    /// The objects that could not be deleted
+    #[serde(default, rename = "Error")]
+    pub errors: Vec<DeleteError>,
+}
+
+/// An object deleted by [`S3Client::delete_objects`](crate::client::S3Client::delete_objects)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeletedObject {
+    /// The key of the deleted object
+    pub key: String,
+    /// The version of the deleted object, if versioning is enabled
+    pub version_id: Option<String>,
+}
+
+/// An object that [`S3Client::delete_objects`](crate::client::S3Client::delete_objects)
+/// failed to delete
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteError {
+    /// The key of the object
+    pub key: String,
+    /// The S3 error code, e.g. `AccessDenied`
+    pub code: String,
+    /// A human readable description of the error
+    #[serde(default)]
+    pub message: String,
+}
+
+/// How quickly [`S3Client::restore_object`](crate::client::S3Client::restore_object)
+/// restores an archived object, which determines its cost
+///
+/// <https://docs.aws.amazon.com/AmazonS3/latest/userguide/restoring-objects-retrieval-options.html>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreTier {
+    /// Typically within 3 to 5 hours
+    Standard,
+    /// Typically within 5 to 12 hours, the cheapest tier
+    Bulk,
+    /// Typically within 1 to 5 minutes, not available for `DEEP_ARCHIVE`
+    Expedited,
+}
+
+impl RestoreTier {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Bulk => "Bulk",
+            Self::Expedited => "Expedited",
+        }
+    }
+}
+
+/// The request body of `RestoreObject`
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct RestoreRequest {
+    pub days: u32,
+    pub glacier_job_parameters: GlacierJobParameters,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct GlacierJobParameters {
+    pub tier: &'static str,
+}
+
+/// The body of `GetBucketVersioning` and `PutBucketVersioning`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct VersioningConfiguration {
+    /// `Enabled` or `Suspended`, absent if versioning was never enabled
+    pub status: Option<String>,
+}
+
+/// The body of `GetObjectTagging` and `PutObjectTagging`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Tagging {
+    pub tag_set: TagSet,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TagSet {
+    #[serde(rename = "Tag", default)]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Tag {
+    pub key: String,
+    pub value: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_validate_byte_range() {
+        for range in [
+            ByteRange::FromStartTo(0, 0),
+            ByteRange::FromStartTo(10, 5),
+            ByteRange::Last(0),
+        ] {
+            assert!(
+                matches!(range.validate(), Err(S3ClientError::InvalidRange(_))),
+                "{range:?} should be rejected"
+            );
+        }
+
+        for range in [
+            ByteRange::FromStartTo(0, 1),
+            ByteRange::FromStartTo(10, 20),
+            ByteRange::FromStart(0),
+            ByteRange::Last(100),
+        ] {
+            assert_eq!(range.validate().unwrap(), range);
+        }
+    }
+
+    #[test]
+    fn test_parse_list_buckets() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListAllMyBucketsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Owner>
+    <ID>bcaf1ffd86f461ca5fb16fd081034f</ID>
+    <DisplayName>webfile</DisplayName>
+  </Owner>
+  <Buckets>
+    <Bucket>
+      <Name>quotes</Name>
+      <CreationDate>2006-02-03T16:45:09.000Z</CreationDate>
+    </Bucket>
+    <Bucket>
+      <Name>samples</Name>
+      <CreationDate>2006-02-03T16:41:58.000Z</CreationDate>
+    </Bucket>
+  </Buckets>
+</ListAllMyBucketsResult>"#;
+
+        let output: ListBucketsOutput = quick_xml::de::from_str(body).unwrap();
+        assert_eq!(
+            output,
+            ListBucketsOutput {
+                buckets: vec![
+                    Bucket {
+                        name: "quotes".into(),
+                        creation_date: "2006-02-03T16:45:09Z".parse().unwrap(),
+                    },
+                    Bucket {
+                        name: "samples".into(),
+                        creation_date: "2006-02-03T16:41:58Z".parse().unwrap(),
+                    },
+                ],
+                owner: Some(Owner {
+                    id: Some("bcaf1ffd86f461ca5fb16fd081034f".into()),
+                    display_name: Some("webfile".into()),
+                }),
+            }
+        );
+
+        let empty = "<ListAllMyBucketsResult><Buckets></Buckets></ListAllMyBucketsResult>";
+        let output: ListBucketsOutput = quick_xml::de::from_str(empty).unwrap();
+        assert!(output.buckets.is_empty());
+        assert_eq!(output.owner, None);
+    }
+
+    #[test]
+    fn test_get_object_options() {
+        let options = GetObjectOptions {
+            range: Some(ByteRange::FromStart(10)),
+            if_match: Some("\"abc\"".into()),
+            if_none_match: Some("\"def\"".into()),
+            if_modified_since: Some("2015-10-21T07:28:00Z".parse().unwrap()),
+            if_unmodified_since: Some("2016-01-02T03:04:05Z".parse().unwrap()),
+            sse_customer_key: Some(CustomerKey([0; 32])),
+        };
+
+        let request = options.apply(request::Builder::new()).unwrap();
+        let headers = request.headers_ref().unwrap();
+        assert_eq!(headers[RANGE], "bytes=10-");
+        assert_eq!(headers[IF_MATCH], "\"abc\"");
+        assert_eq!(headers[IF_NONE_MATCH], "\"def\"");
+        assert_eq!(headers[IF_MODIFIED_SINCE], "Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(
+            headers[IF_UNMODIFIED_SINCE],
+            "Sat, 02 Jan 2016 03:04:05 GMT"
+        );
+        assert_eq!(headers[SSE_CUSTOMER_ALGORITHM_HEADER], "AES256");
+        assert_eq!(
+            headers[SSE_CUSTOMER_KEY_HEADER],
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="
+        );
+        assert_eq!(
+            headers[SSE_CUSTOMER_KEY_MD5_HEADER],
+            "cLyPS3KoaSFGi/joRB3OUQ=="
+        );
+
+        let request = GetObjectOptions::default()
+            .apply(request::Builder::new())
+            .unwrap();
+        assert!(request.headers_ref().unwrap().is_empty());
+
+        let options = GetObjectOptions::from(Some(ByteRange::Last(5)));
+        assert_eq!(options.range, Some(ByteRange::Last(5)));
+        assert_eq!(options.if_match, None);
+
+        let options = GetObjectOptions {
+            range: Some(ByteRange::Last(0)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            options.apply(request::Builder::new()),
+            Err(S3ClientError::InvalidRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_retention_xml() {
+        let retention = Retention {
+            mode: ObjectLockMode::Governance,
+            retain_until_date: Utc.with_ymd_and_hms(2030, 1, 2, 3, 4, 5).unwrap(),
+        };
+        let xml = quick_xml::se::to_string(&retention).unwrap();
+        assert_eq!(
+            xml,
+            "<Retention><Mode>GOVERNANCE</Mode>\
+             <RetainUntilDate>2030-01-02T03:04:05Z</RetainUntilDate></Retention>"
+        );
+        let parsed: Retention = quick_xml::de::from_str(&xml).unwrap();
+        assert_eq!(parsed, retention);
+
+        // As returned by GetObjectRetention
+        let parsed: Retention = quick_xml::de::from_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Retention xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Mode>COMPLIANCE</Mode>
+  <RetainUntilDate>2030-01-02T03:04:05.000Z</RetainUntilDate>
+</Retention>"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.mode, ObjectLockMode::Compliance);
+        assert_eq!(parsed.retain_until_date, retention.retain_until_date);
+
+        let err = quick_xml::de::from_str::<Retention>(
+            "<Retention><Mode>NONE</Mode>\
+             <RetainUntilDate>2030-01-02T03:04:05Z</RetainUntilDate></Retention>",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown object lock mode NONE"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_parse_canned_acl() {
+        for acl in CannedAcl::ALL {
+            assert_eq!(acl.as_str().parse::<CannedAcl>().unwrap(), acl);
+        }
+        assert_eq!(
+            "bucket-owner-full-control".parse::<CannedAcl>().unwrap(),
+            CannedAcl::BucketOwnerFullControl
+        );
+
+        for name in ["", "PRIVATE", "public", "log-delivery-write"] {
+            let err = name.parse::<CannedAcl>().unwrap_err();
+            assert!(
+                matches!(&err, S3ClientError::InvalidArgument(msg) if msg.contains("unknown canned ACL")),
+                "{err:?}"
+            );
+        }
+    }
+
+    /// <https://docs.aws.amazon.com/AmazonS3/latest/API/API_GetObjectAcl.html#API_GetObjectAcl_Examples>
+    #[test]
+    fn test_parse_access_control_policy() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<AccessControlPolicy xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Owner>
+    <ID>75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a</ID>
+    <DisplayName>mtd@amazon.com</DisplayName>
+  </Owner>
+  <AccessControlList>
+    <Grant>
+      <Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CanonicalUser">
+        <ID>75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a</ID>
+        <DisplayName>mtd@amazon.com</DisplayName>
+      </Grantee>
+      <Permission>FULL_CONTROL</Permission>
+    </Grant>
+    <Grant>
+      <Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Group">
+        <URI>http://acs.amazonaws.com/groups/global/AllUsers</URI>
+      </Grantee>
+      <Permission>READ</Permission>
+    </Grant>
+  </AccessControlList>
+</AccessControlPolicy>"#;
+
+        let policy: AccessControlPolicy = quick_xml::de::from_str(xml).unwrap();
+        let owner = policy.owner.unwrap();
+        assert_eq!(
+            owner.id.as_deref(),
+            Some("75aa57f09aa0c8caeab4f8c24e99d10f8e7faeebf76c078efc7c6caea54ba06a")
+        );
+        assert_eq!(policy.grants.len(), 2);
+
+        let user = &policy.grants[0];
+        assert_eq!(user.grantee.grantee_type, "CanonicalUser");
+        assert_eq!(user.grantee.id, owner.id);
+        assert_eq!(user.grantee.display_name.as_deref(), Some("mtd@amazon.com"));
+        assert_eq!(user.grantee.uri, None);
+        assert_eq!(user.permission, "FULL_CONTROL");
+
+        let group = &policy.grants[1];
+        assert_eq!(group.grantee.grantee_type, "Group");
+        assert_eq!(group.grantee.id, None);
+        assert_eq!(
+            group.grantee.uri.as_deref(),
+            Some("http://acs.amazonaws.com/groups/global/AllUsers")
+        );
+        assert_eq!(group.permission, "READ");
+
+        let empty = "<AccessControlPolicy><AccessControlList/></AccessControlPolicy>";
+        let policy: AccessControlPolicy = quick_xml::de::from_str(empty).unwrap();
+        assert_eq!(policy.owner, None);
+        assert!(policy.grants.is_empty());
+    }
+
+    #[test]
+    fn test_select_object_content_request() {
+        let cases = [
+            (
+                InputFormat::Json { lines: true },
+                OutputFormat::Json,
+                "<InputSerialization><JSON><Type>LINES</Type></JSON></InputSerialization>\
+                 <OutputSerialization><JSON><RecordDelimiter>\n</RecordDelimiter></JSON>\
+                 </OutputSerialization>",
+            ),
+            (
+                InputFormat::Parquet,
+                OutputFormat::Csv,
+                "<InputSerialization><Parquet/></InputSerialization>\
+                 <OutputSerialization><CSV/></OutputSerialization>",
+            ),
+            (
+                InputFormat::Csv { header: false },
+                OutputFormat::Csv,
+                "<InputSerialization><CSV><FileHeaderInfo>NONE</FileHeaderInfo></CSV>\
+                 </InputSerialization><OutputSerialization><CSV/></OutputSerialization>",
+            ),
+        ];
+
+        for (input, output, expected) in cases {
+            let request = SelectObjectContentRequest::new("SELECT * FROM S3Object", input, output);
+            assert_eq!(
+                quick_xml::se::to_string(&request).unwrap(),
+                format!(
+                    "<SelectObjectContentRequest>\
+                     <Expression>SELECT * FROM S3Object</Expression>\
+                     <ExpressionType>SQL</ExpressionType>{expected}\
+                     </SelectObjectContentRequest>"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_storage_class() {
+        for class in StorageClass::ALL {
+            assert_eq!(class.as_str().parse::<StorageClass>().unwrap(), class);
+        }
+        assert_eq!(
+            "DEEP_ARCHIVE".parse::<StorageClass>().unwrap(),
+            StorageClass::DeepArchive
+        );
+
+        for name in ["", "standard", "GLACIER_DEEP", "STANDARD "] {
+            let err = name.parse::<StorageClass>().unwrap_err();
+            assert!(
+                matches!(&err, S3ClientError::InvalidArgument(msg) if msg.contains("unknown storage class")),
+                "{err:?}"
+            );
+        }
+    }
+}
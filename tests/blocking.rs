@@ -0,0 +1,33 @@
+mod common;
+
+use common::{builder, credential, REGION};
+use s3_client::error::S3ClientError;
+use s3_client::testing::MockS3;
+
+#[test]
+fn blocking_get_outside_a_runtime() {
+    // The server runs on its own runtime, while the client blocks the test thread
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime
+        .block_on(MockS3::start(credential(), REGION))
+        .unwrap();
+    server.insert_object("bucket", "key", "hello");
+    let client = builder(&server, credential()).build_blocking().unwrap();
+
+    assert_eq!(client.get("bucket", "key", None).unwrap(), "hello");
+    client
+        .put_object("bucket", "other", "world".into())
+        .unwrap();
+    assert_eq!(server.object("bucket", "other").unwrap(), "world");
+    assert!(server.requests().iter().all(|r| r.signature_valid));
+}
+
+#[tokio::test]
+async fn blocking_get_inside_a_runtime_fails() {
+    let server = MockS3::start(credential(), REGION).await.unwrap();
+    let client = builder(&server, credential()).build_blocking().unwrap();
+
+    let err = client.get("bucket", "key", None).unwrap_err();
+    assert!(matches!(err, S3ClientError::BlockingInAsyncContext));
+    assert!(server.requests().is_empty());
+}
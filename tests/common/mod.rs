@@ -0,0 +1,36 @@
+//! Setup shared by the integration tests, which run against [`MockS3`]
+
+#![allow(dead_code)]
+
+use s3_client::client::{S3Client, S3ClientBuilder};
+use s3_client::credentials::{AwsCredential, StaticCredentialProvider};
+use s3_client::testing::MockS3;
+use s3_client::tokio::HyperConnector;
+use std::sync::Arc;
+
+pub const REGION: &str = "us-east-1";
+
+pub fn credential() -> Arc<AwsCredential> {
+    Arc::new(AwsCredential {
+        key_id: "AKIDEXAMPLE".into(),
+        secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+        token: None,
+        expiry: None,
+    })
+}
+
+/// Returns a builder for a client of `server` signing with `credential`
+pub fn builder(server: &MockS3, credential: Arc<AwsCredential>) -> S3ClientBuilder {
+    S3ClientBuilder::default()
+        .endpoint(server.endpoint())
+        .region(REGION)
+        .force_path_style(true)
+        .credentials(Arc::new(StaticCredentialProvider { credential }))
+}
+
+/// Starts a server and returns it with a client of it
+pub async fn start() -> (MockS3, S3Client<HyperConnector>) {
+    let server = MockS3::start(credential(), REGION).await.unwrap();
+    let client = builder(&server, credential()).build_tokio().unwrap();
+    (server, client)
+}
@@ -0,0 +1,39 @@
+//! The environment is shared by all tests of a binary, so it is only modified by
+//! the single test in this one
+
+use s3_client::credentials::{CredentialProvider, EnvironmentCredentialProvider};
+use s3_client::error::S3ClientError;
+
+#[tokio::test]
+async fn environment_credentials() {
+    let provider = EnvironmentCredentialProvider::new();
+    std::env::set_var("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE");
+    std::env::set_var("AWS_SECRET_ACCESS_KEY", "secret");
+    std::env::set_var("AWS_SESSION_TOKEN", "token");
+
+    let credential = provider.get_credential().await.unwrap();
+    assert_eq!(credential.key_id, "AKIDEXAMPLE");
+    assert_eq!(credential.secret_key, "secret");
+    assert_eq!(credential.token.as_deref(), Some("token"));
+
+    // The environment is read again on every call
+    std::env::remove_var("AWS_SESSION_TOKEN");
+    let credential = provider.get_credential().await.unwrap();
+    assert_eq!(credential.key_id, "AKIDEXAMPLE");
+    assert_eq!(credential.token, None);
+
+    std::env::set_var("AWS_SESSION_TOKEN", "");
+    assert_eq!(provider.get_credential().await.unwrap().token, None);
+
+    std::env::remove_var("AWS_SECRET_ACCESS_KEY");
+    let err = provider.get_credential().await.unwrap_err();
+    assert!(
+        matches!(err, S3ClientError::CredentialsNotFound(ref msg) if msg.contains("AWS_SECRET_ACCESS_KEY"))
+    );
+
+    std::env::remove_var("AWS_ACCESS_KEY_ID");
+    let err = provider.get_credential().await.unwrap_err();
+    assert!(
+        matches!(err, S3ClientError::CredentialsNotFound(ref msg) if msg.contains("AWS_ACCESS_KEY_ID"))
+    );
+}
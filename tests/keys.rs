@@ -0,0 +1,78 @@
+mod common;
+
+use bytes::Bytes;
+use common::start;
+use hyper::{Method, StatusCode};
+use s3_client::testing::MockResponse;
+
+/// Keys that need encoding, and their encoding in the request path
+const KEYS: &[(&str, &str)] = &[
+    ("with space", "/bucket/with%20space"),
+    ("my file+name=1.txt", "/bucket/my%20file%2Bname%3D1.txt"),
+    ("折り紙.txt", "/bucket/%E6%8A%98%E3%82%8A%E7%B4%99.txt"),
+    ("a+b=c", "/bucket/a%2Bb%3Dc"),
+    ("100%", "/bucket/100%25"),
+    (
+        "ünïcödé/ключ",
+        "/bucket/%C3%BCn%C3%AFc%C3%B6d%C3%A9/%D0%BA%D0%BB%D1%8E%D1%87",
+    ),
+    ("dir//file", "/bucket/dir//file"),
+    ("q?x=1&y#z", "/bucket/q%3Fx%3D1%26y%23z"),
+    ("~tilde_-.txt", "/bucket/~tilde_-.txt"),
+];
+
+#[tokio::test]
+async fn keys_are_encoded_and_signed() {
+    let (server, client) = start().await;
+
+    for (key, _) in KEYS {
+        client
+            .put_object("bucket", key, Bytes::from(key.to_string()))
+            .await
+            .unwrap();
+        let output = client.get_object("bucket", key, None).await.unwrap();
+        assert_eq!(output.body, key.as_bytes(), "{key}");
+        assert_eq!(server.object("bucket", key).unwrap(), key.as_bytes());
+    }
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), KEYS.len() * 2);
+    for (request, (key, path)) in requests.chunks(2).zip(KEYS) {
+        for request in request {
+            assert!(request.signature_valid, "{key}");
+            assert_eq!(request.uri.path(), *path);
+            assert_eq!(request.uri.query(), None);
+            // S3 signs the path as sent rather than encoding it again
+            let canonical = request.canonical_request.as_deref().unwrap();
+            assert_eq!(canonical.lines().nth(1), Some(*path));
+        }
+    }
+}
+
+#[tokio::test]
+async fn copy_source_is_encoded() {
+    let (server, client) = start().await;
+    let result = "<CopyObjectResult><ETag>\"etag\"</ETag>\
+                  <LastModified>2023-01-01T00:00:00.000Z</LastModified></CopyObjectResult>";
+    for (_, path) in KEYS {
+        let dst = path.replace("/bucket/", "/dst/");
+        server.respond(
+            Method::PUT,
+            &dst,
+            MockResponse::new(StatusCode::OK).body(result),
+        );
+    }
+
+    for (key, _) in KEYS {
+        let output = client.copy_object("bucket", key, "dst", key).await.unwrap();
+        assert_eq!(output.e_tag.as_deref(), Some("\"etag\""));
+    }
+
+    let requests = server.requests();
+    for (request, (key, path)) in requests.iter().zip(KEYS) {
+        assert!(request.signature_valid, "{key}");
+        assert_eq!(request.uri.path(), path.replace("/bucket/", "/dst/"));
+        // The source is encoded like the path, but without its leading slash
+        assert_eq!(request.headers["x-amz-copy-source"], path[1..]);
+    }
+}
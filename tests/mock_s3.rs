@@ -0,0 +1,272 @@
+mod common;
+
+use bytes::Bytes;
+use common::{builder, start, REGION};
+use futures::stream;
+use hyper::{Method, StatusCode};
+use s3_client::client::S3ClientBuilder;
+use s3_client::credentials::AwsCredential;
+use s3_client::error::S3ClientError;
+use s3_client::store::ObjectStore;
+use s3_client::testing::{MockResponse, MockS3};
+use s3_client::types::ByteRange;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn put_get_round_trip() {
+    let (server, client) = start().await;
+
+    client
+        .put_object("bucket", "dir/key.txt", Bytes::from_static(b"hello world"))
+        .await
+        .unwrap();
+    let output = client
+        .get_object("bucket", "dir/key.txt", None)
+        .await
+        .unwrap();
+
+    assert_eq!(output.body, "hello world");
+    assert_eq!(
+        server.object("bucket", "dir/key.txt").unwrap(),
+        "hello world"
+    );
+    let requests = server.requests();
+    assert!(requests.iter().all(|r| r.signature_valid));
+    // The signature covers the hash of the body
+    assert_eq!(requests[0].method, Method::PUT);
+    assert_eq!(
+        requests[0].headers["x-amz-content-sha256"],
+        sha256_hex(b"hello world")
+    );
+}
+
+#[tokio::test]
+async fn payload_hash_matches_body() {
+    let (server, client) = start().await;
+    let body = (0..10_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    client
+        .put_object("bucket", "key", Bytes::from(body.clone()))
+        .await
+        .unwrap();
+    client
+        .put_object("bucket", "empty", Bytes::new())
+        .await
+        .unwrap();
+    client.get_object("bucket", "key", None).await.unwrap();
+
+    for request in server.requests() {
+        assert!(request.signature_valid);
+        assert_eq!(
+            request.headers["x-amz-content-sha256"],
+            sha256_hex(&request.body),
+            "{} {}",
+            request.method,
+            request.uri
+        );
+    }
+    assert_eq!(server.requests()[0].body, body);
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[tokio::test]
+async fn ranged_get() {
+    let (server, client) = start().await;
+    server.insert_object("bucket", "key", "0123456789");
+
+    let get = |range| client.get_object("bucket", "key", Some(range));
+    assert_eq!(get(ByteRange::FromStartTo(2, 5)).await.unwrap().body, "234");
+    assert_eq!(get(ByteRange::FromStart(7)).await.unwrap().body, "789");
+    assert_eq!(get(ByteRange::Last(2)).await.unwrap().body, "89");
+
+    let ranges = server
+        .requests()
+        .iter()
+        .map(|r| r.headers["range"].to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, ["bytes=2-4", "bytes=7-", "bytes=-2"]);
+}
+
+#[tokio::test]
+async fn download_parallel() {
+    let (server, client) = start().await;
+    let data = (0..1000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    server.insert_object("bucket", "key", data.clone());
+
+    // Four full chunks and a partial one, reassembled in order
+    let body = client
+        .download_parallel("bucket", "key", 230, 3)
+        .await
+        .unwrap();
+    assert_eq!(body, data);
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, Method::HEAD);
+    let mut ranges = requests[1..]
+        .iter()
+        .map(|r| r.headers["range"].to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|range| {
+        range[6..]
+            .split('-')
+            .next()
+            .unwrap()
+            .parse::<u32>()
+            .unwrap()
+    });
+    assert_eq!(
+        ranges,
+        [
+            "bytes=0-229",
+            "bytes=230-459",
+            "bytes=460-689",
+            "bytes=690-919",
+            "bytes=920-999"
+        ]
+    );
+    assert!(requests[1..]
+        .iter()
+        .all(|r| r.headers.contains_key("if-match")));
+
+    // Objects of at most one chunk are fetched with a single request
+    server.insert_object("bucket", "small", "hello");
+    let body = client
+        .download_parallel("bucket", "small", 5, 3)
+        .await
+        .unwrap();
+    assert_eq!(body, "hello");
+    let requests = server.requests();
+    assert!(!requests.last().unwrap().headers.contains_key("range"));
+
+    let err = client
+        .download_parallel("bucket", "key", 0, 3)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, S3ClientError::InvalidArgument(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn wrong_secret_is_rejected() {
+    let server = MockS3::start(common::credential(), REGION).await.unwrap();
+    let wrong = Arc::new(AwsCredential {
+        key_id: "AKIDEXAMPLE".into(),
+        secret_key: "not the secret".into(),
+        token: None,
+        expiry: None,
+    });
+    let client = builder(&server, wrong).build_tokio().unwrap();
+
+    let err = client.get_object("bucket", "key", None).await.unwrap_err();
+
+    assert!(
+        matches!(err, S3ClientError::ServiceError { ref response, .. }
+        if response.code == "SignatureDoesNotMatch")
+    );
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].signature_valid);
+    assert!(requests[0].canonical_request.is_some());
+}
+
+#[tokio::test]
+async fn streaming_put_is_decoded() {
+    let (server, client) = start().await;
+    // Spans two 64 KiB chunks and a partial one
+    let data = (0..150_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    let parts = data
+        .chunks(10_000)
+        .map(Bytes::copy_from_slice)
+        .collect::<Vec<_>>();
+
+    client
+        .put_object_streaming("bucket", "key", stream::iter(parts), data.len() as u64)
+        .await
+        .unwrap();
+
+    let request = &server.requests()[0];
+    assert!(request.signature_valid);
+    assert_eq!(request.headers["content-encoding"], "aws-chunked");
+    assert_eq!(request.headers["x-amz-decoded-content-length"], "150000");
+    assert_ne!(request.body.as_ref(), data.as_slice());
+    assert_eq!(server.object("bucket", "key").unwrap(), data);
+}
+
+#[tokio::test]
+async fn canned_responses() {
+    let (server, client) = start().await;
+    server.insert_object("bucket", "key", "stored");
+    server.respond(
+        Method::GET,
+        "/bucket/key",
+        MockResponse::error(StatusCode::SERVICE_UNAVAILABLE, "SlowDown", "Slow down"),
+    );
+    server.respond(
+        Method::GET,
+        "/bucket/key",
+        MockResponse::new(StatusCode::OK)
+            .header("content-type", "text/plain")
+            .body("canned"),
+    );
+
+    // The transient error is retried, and queued responses are returned before the object
+    let output = client.get_object("bucket", "key", None).await.unwrap();
+    assert_eq!(output.body, "canned");
+    assert_eq!(output.metadata.content_type.as_deref(), Some("text/plain"));
+    let output = client.get_object("bucket", "key", None).await.unwrap();
+    assert_eq!(output.body, "stored");
+    assert_eq!(server.requests().len(), 3);
+
+    let err = client
+        .get_object("bucket", "missing", None)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, S3ClientError::ServiceError { ref response, .. }
+        if response.code == "NoSuchKey"),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn anonymous_server() {
+    let server = MockS3::start_anonymous().await.unwrap();
+    server.insert_object("bucket", "key", "public");
+    let client = S3ClientBuilder::default()
+        .endpoint(server.endpoint())
+        .region(REGION)
+        .force_path_style(true)
+        .anonymous(true)
+        .build_tokio()
+        .unwrap();
+
+    let output = client.get_object("bucket", "key", None).await.unwrap();
+    assert_eq!(output.body, "public");
+    let requests = server.requests();
+    assert!(requests[0].signature_valid);
+    assert_eq!(requests[0].canonical_request, None);
+}
+
+#[tokio::test]
+async fn client_as_object_store() {
+    let (server, client) = start().await;
+    let store: Box<dyn ObjectStore> = Box::new(client);
+
+    store
+        .put_object("bucket", "key", Bytes::from_static(b"hello"))
+        .await
+        .unwrap();
+    assert_eq!(store.get("bucket", "key", None).await.unwrap(), "hello");
+    let metadata = store.head_object("bucket", "key").await.unwrap();
+    assert_eq!(metadata.content_length, Some(5));
+    store.delete_object("bucket", "key").await.unwrap();
+
+    assert_eq!(server.object("bucket", "key"), None);
+    assert!(server.requests().iter().all(|r| r.signature_valid));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use chrono::Utc;
+use common::{builder, credential, REGION};
+use hyper::{Method, StatusCode};
+use s3_client::client::HttpConfig;
+use s3_client::retry::RetryConfig;
+use s3_client::testing::{MockResponse, MockS3};
+use std::time::Duration;
+
+#[tokio::test]
+async fn clock_skew_correction_does_not_consume_retries() {
+    let server = MockS3::start(credential(), REGION).await.unwrap();
+    server.insert_object("bucket", "key", "hello");
+    let skewed = format!(
+        "<Error><Code>RequestTimeTooSkewed</Code><Message>skewed</Message>\
+         <ServerTime>{}</ServerTime></Error>",
+        Utc::now().to_rfc3339()
+    );
+    server.respond(
+        Method::GET,
+        "/bucket/key",
+        MockResponse::new(StatusCode::FORBIDDEN).body(skewed),
+    );
+    server.respond(
+        Method::GET,
+        "/bucket/key",
+        MockResponse::error(StatusCode::SERVICE_UNAVAILABLE, "SlowDown", "slow down"),
+    );
+
+    let client = builder(&server, credential())
+        .http_config(HttpConfig {
+            retry: RetryConfig {
+                max_attempts: 2,
+                base_delay: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .build_tokio()
+        .unwrap();
+
+    let output = client.get_object("bucket", "key", None).await.unwrap();
+
+    assert_eq!(output.body, "hello");
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn retries_are_bounded() {
+    let server = MockS3::start(credential(), REGION).await.unwrap();
+    server.insert_object("bucket", "key", "hello");
+    for _ in 0..2 {
+        server.respond(
+            Method::GET,
+            "/bucket/key",
+            MockResponse::error(StatusCode::SERVICE_UNAVAILABLE, "SlowDown", "slow down"),
+        );
+    }
+
+    let client = builder(&server, credential())
+        .http_config(HttpConfig {
+            retry: RetryConfig {
+                max_attempts: 2,
+                base_delay: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .build_tokio()
+        .unwrap();
+
+    let err = client.get_object("bucket", "key", None).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(server.requests().len(), 2);
+}